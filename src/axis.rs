@@ -0,0 +1,19 @@
+/// One of the three axes of a model.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// What happens to voxels moved past the edge of a model.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Overflow {
+    /// The voxels are removed.
+    Clip,
+    /// The voxels come back on the other side of the model.
+    Wrap,
+    /// The model is made larger so the voxels fit. Voxels moved below 0 move the rest of the
+    /// voxels up to make room. The model can not be larger than 256.
+    Grow,
+}
@@ -0,0 +1,149 @@
+use crate::convert::u32_to_array;
+use crate::error::VoxError;
+use crate::riff::{read_bytes, read_u32};
+use crate::writing::write_slice;
+use std::io;
+use std::io::Write;
+
+/// A single RIFF chunk read from a .vox file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    /// The 4 byte name of the chunk such as `SIZE` or `nTRN`.
+    pub id: [u8; 4],
+    /// Where the chunk starts in the data it was read from.
+    pub offset: usize,
+    pub content: Vec<u8>,
+    pub children: Vec<Chunk>,
+}
+
+impl Chunk {
+    /// Returns the name of the chunk as a string, or None if it is not valid UTF-8.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::ChunkReader;
+    ///
+    /// let bytes = b"NOTE\x00\x00\x00\x00\x00\x00\x00\x00";
+    /// let chunk = ChunkReader::new(bytes).next().unwrap().unwrap();
+    /// assert_eq!(chunk.id_str(), Some("NOTE"));
+    /// ```
+    pub fn id_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.id).ok()
+    }
+
+    //runs the parser over the content with a cursor starting at 0. Offsets in errors are
+    //changed to be from the start of the data the chunk was read from.
+    pub(crate) fn parse<T, F>(&self, parser: F) -> Result<T, VoxError>
+    where
+        F: FnOnce(&[u8], &mut usize) -> Result<T, VoxError>,
+    {
+        let mut cursor = 0;
+        parser(&self.content, &mut cursor).map_err(|err| err.shift_offset(self.offset + 12))
+    }
+
+    //writes the chunk and its children as they were read
+    pub(crate) fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_slice(writer, &self.id)?;
+        write_slice(writer, &u32_to_array(self.content.len() as u32))?;
+        write_slice(writer, &u32_to_array(self.get_children_size() as u32))?;
+        write_slice(writer, &self.content)?;
+        for child in self.children.iter() {
+            child.write(writer)?;
+        }
+        Ok(())
+    }
+
+    //size in bytes when written including the chunk header
+    pub(crate) fn get_size(&self) -> i32 {
+        12 + self.content.len() as i32 + self.get_children_size()
+    }
+
+    fn get_children_size(&self) -> i32 {
+        self.children.iter().map(|child| child.get_size()).sum()
+    }
+}
+
+/// Reads chunks one after another in a single pass. Each chunk has its children read with it.
+///
+/// # Example
+/// ```
+/// use create_vox::{ChunkReader, VoxFile};
+///
+/// let mut vox = VoxFile::new(10, 10, 10);
+/// let bytes = vox.to_bytes();
+///
+/// //skip the "VOX " and version at the start of the file
+/// let main = ChunkReader::new(&bytes[8..]).next().unwrap().unwrap();
+/// assert_eq!(&main.id, b"MAIN");
+/// assert_eq!(&main.children[0].id, b"SIZE");
+/// assert_eq!(&main.children[1].id, b"XYZI");
+/// ```
+pub struct ChunkReader<'a> {
+    data: &'a [u8],
+    cursor: usize,
+    end: usize,
+    //how many chunks the chunks being read are inside of
+    depth: usize,
+    failed: bool,
+}
+
+//the deepest chunks can be inside other chunks. Files from MagicaVoxel only have chunks inside
+//MAIN.
+const MAX_CHUNK_DEPTH: usize = 64;
+
+impl<'a> ChunkReader<'a> {
+    /// Creates a reader for the chunks in the data given.
+    pub fn new(data: &'a [u8]) -> ChunkReader<'a> {
+        ChunkReader::from_range(data, 0, data.len())
+    }
+
+    //reads chunks between start and end. Offsets are from the start of data.
+    pub(crate) fn from_range(data: &'a [u8], start: usize, end: usize) -> ChunkReader<'a> {
+        ChunkReader {
+            data,
+            cursor: start,
+            end,
+            depth: 0,
+            failed: false,
+        }
+    }
+
+    fn read_chunk(&mut self) -> Result<Chunk, VoxError> {
+        let data = &self.data[..self.end];
+        let offset = self.cursor;
+        let id = read_bytes(data, &mut self.cursor, 4)?;
+        let id = [id[0], id[1], id[2], id[3]];
+        let content_size = read_u32(data, &mut self.cursor)? as usize;
+        let children_size = read_u32(data, &mut self.cursor)? as usize;
+        let content = read_bytes(data, &mut self.cursor, content_size)?.to_vec();
+
+        let children_start = self.cursor;
+        read_bytes(data, &mut self.cursor, children_size)?;
+        if children_size > 0 && self.depth >= MAX_CHUNK_DEPTH {
+            return Err(VoxError::InvalidChunk { offset });
+        }
+        let mut children_reader = ChunkReader::from_range(self.data, children_start, self.cursor);
+        children_reader.depth = self.depth + 1;
+        let children = children_reader.collect::<Result<Vec<Chunk>, VoxError>>()?;
+
+        Ok(Chunk {
+            id,
+            offset,
+            content,
+            children,
+        })
+    }
+}
+
+impl<'a> Iterator for ChunkReader<'a> {
+    type Item = Result<Chunk, VoxError>;
+
+    fn next(&mut self) -> Option<Result<Chunk, VoxError>> {
+        if self.failed || self.cursor >= self.end {
+            return None;
+        }
+        let chunk = self.read_chunk();
+        self.failed = chunk.is_err();
+        Some(chunk)
+    }
+}
@@ -1,33 +1,99 @@
-use crate::node::Transform;
-use crate::node::{Node, NodeAttributes, NodeType};
+use crate::node::{Node, NodeExtras, NodeTransform};
+use crate::rotation::Rotation;
+use crate::transform::{Keyframe, Transform};
 
+/// Another placement of a model in the scene. The voxels are not copied, only the model id is
+/// used.
+#[derive(Debug, Clone)]
 pub struct ModelCopy {
     pub model_id: i32,
     pub position: Option<(i32, i32, i32)>,
-    pub(crate) rotation: Option<u8>,
+    pub rotation: Option<Rotation>,
     pub layer: Option<i32>,
     pub name: Option<String>,
+    pub hidden: Option<bool>,
+    /// Keyframes of the animation after the first frame, with the frame index they start at.
+    pub frames: Vec<(u32, Keyframe)>,
+    /// Other models shown in the animation, with the frame index they start at. The model
+    /// of the copy is shown from the first frame.
+    pub model_frames: Vec<(u32, i32)>,
+    //id of the group the copy is in. None is the root group.
+    pub(crate) group: Option<i32>,
+    pub(crate) extras: NodeExtras,
 }
 
 impl ModelCopy {
-    pub(crate) fn to_node(&self) -> Node {
-        let mut attributes = NodeAttributes::new();
-        attributes.name = self.name.clone();
-        let mut transform_node = Node::new(NodeType::Transform(self.transform_data()), attributes);
-        let shape_node = Node::new(NodeType::Shape(self.model_id), NodeAttributes::new());
-        transform_node.add_child(shape_node);
-
-        transform_node
+    /// Creates a copy of the model with the id given using the transform for where it is placed.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{ModelCopy, Transform};
+    ///
+    /// let copy = ModelCopy::new(0, Transform::default());
+    /// assert_eq!(copy.position, None);
+    /// ```
+    pub fn new(model_id: i32, transform: Transform) -> ModelCopy {
+        ModelCopy {
+            model_id,
+            position: transform.translation,
+            rotation: transform.rotation,
+            layer: transform.layer,
+            name: transform.name,
+            hidden: transform.hidden,
+            frames: vec![],
+            model_frames: vec![],
+            group: None,
+            extras: NodeExtras::default(),
+        }
     }
 
-    pub(crate) fn transform_data(&self) -> Transform {
+    /// Returns where the copy is placed and how it is shown.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{ModelCopy, Transform};
+    ///
+    /// let transform = Transform {
+    ///     translation: Some((1, 2, 3)),
+    ///     ..Transform::default()
+    /// };
+    /// let copy = ModelCopy::new(0, transform.clone());
+    /// assert_eq!(copy.transform(), transform);
+    /// ```
+    pub fn transform(&self) -> Transform {
         Transform {
-            layer: self.layer.unwrap_or_else(|| 0),
-            rotation: match self.rotation {
-                None => None,
-                Some(rot) => Some(rot as i32),
-            },
             translation: self.position,
+            rotation: self.rotation,
+            layer: self.layer,
+            name: self.name.clone(),
+            hidden: self.hidden,
         }
     }
+
+    /// Returns the id of the group the copy is in. None is the root of the scene.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{ModelCopy, Transform};
+    ///
+    /// let copy = ModelCopy::new(0, Transform::default());
+    /// assert_eq!(copy.group(), None);
+    /// ```
+    pub fn group(&self) -> Option<i32> {
+        self.group
+    }
+
+    pub(crate) fn to_node(&self) -> Node {
+        self.extras.to_node(
+            self.name.clone(),
+            self.hidden,
+            self.transform_data(),
+            self.extras.shape(self.model_id, &self.model_frames),
+        )
+    }
+
+    pub(crate) fn transform_data(&self) -> NodeTransform {
+        self.extras
+            .transform(self.layer, self.rotation, self.position, &self.frames)
+    }
 }
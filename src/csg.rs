@@ -0,0 +1,216 @@
+use crate::model::Model;
+use crate::placemode::PlaceMode;
+use crate::voxel::Voxel;
+use std::collections::HashSet;
+
+//the offset is where voxel (0, 0, 0) of the other model is in this model. Union and xor make this
+//model larger to fit the other one and the other operations only change voxels in this model.
+impl Model {
+    /// Adds the voxels of the other model at the offset. Where both models have a voxel the
+    /// place mode of this model picks the color. The model grows to fit the other one, moving
+    /// its voxels if the offset is negative. It will return an error if the models together are
+    /// larger than 256 on an axis or if the place mode is PlaceMode::Error and a voxel overlaps.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, PlaceMode};
+    ///
+    /// let mut wall = Model::new(10, 1, 10);
+    /// wall.add_cube(0, 0, 0, 10, 1, 10, 1).unwrap();
+    /// let mut tower = Model::new(4, 4, 20);
+    /// tower.add_cube(0, 0, 0, 4, 4, 20, 2).unwrap();
+    ///
+    /// //the wall keeps its color where they meet
+    /// wall.place_mode = PlaceMode::KeepExisting;
+    /// wall.union(&tower, (8, -2, 0)).unwrap();
+    /// assert_eq!(wall.size, (12, 4, 20));
+    /// assert_eq!(wall.get(9, 2, 0), Some(1));
+    /// assert_eq!(wall.get(11, 0, 19), Some(2));
+    /// ```
+    pub fn union(&mut self, other: &Model, offset: (i32, i32, i32)) -> Result<(), &str> {
+        if let PlaceMode::Error = self.place_mode {
+            if self.overlapping(other, offset).next().is_some() {
+                return Err("Voxel already at position");
+            }
+        }
+        let offset = self.grow_to_fit(other, offset)?;
+
+        self.place_voxels(other.voxels.iter().map(move |voxel| moved(voxel, offset)))
+    }
+
+    /// Removes the voxels where the other model at the offset has voxels.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut wall = Model::new(20, 2, 10);
+    /// wall.add_cube(0, 0, 0, 20, 2, 10, 1).unwrap();
+    /// let mut window = Model::new(4, 2, 5);
+    /// window.add_cube(0, 0, 0, 4, 2, 5, 1).unwrap();
+    ///
+    /// //carve two windows
+    /// wall.subtract(&window, (3, 0, 3));
+    /// wall.subtract(&window, (13, 0, 3));
+    /// assert!(!wall.is_voxel_at_pos(5, 1, 5));
+    /// assert_eq!(wall.num_of_voxels(), 400 - 2 * 40);
+    /// ```
+    pub fn subtract(&mut self, other: &Model, offset: (i32, i32, i32)) {
+        let positions: Vec<(u8, u8, u8)> = self.overlapping(other, offset).collect();
+        for position in positions {
+            self.voxels.remove(position);
+        }
+    }
+
+    /// Only keeps the voxels where the other model at the offset also has voxels. The colors of
+    /// this model are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_cube(0, 0, 0, 10, 10, 10, 1).unwrap();
+    /// let mut ball = Model::new(10, 10, 10);
+    /// ball.add_sphere((4.5, 4.5, 4.5), 5.0, 1).unwrap();
+    ///
+    /// model.intersect(&ball, (5, 0, 0));
+    /// assert_eq!(model.num_of_voxels(), ball.num_of_voxels() / 2);
+    /// ```
+    pub fn intersect(&mut self, other: &Model, offset: (i32, i32, i32)) {
+        let keep: HashSet<(u8, u8, u8)> = self.overlapping(other, offset).collect();
+        self.voxels.retain(|voxel| keep.contains(&voxel.position));
+    }
+
+    /// Keeps the voxels that are only in one of the models. The model grows to fit the other
+    /// one like in union().
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut a = Model::new(4, 4, 1);
+    /// a.add_cube(0, 0, 0, 4, 4, 1, 1).unwrap();
+    /// let mut b = a.clone();
+    /// b.change_voxels(|voxel| voxel.colorindex = 2);
+    ///
+    /// a.xor(&b, (2, 2, 0)).unwrap();
+    /// assert_eq!(a.size, (6, 6, 1));
+    /// assert_eq!(a.num_of_voxels(), 2 * (16 - 4));
+    /// assert_eq!(a.get(5, 5, 0), Some(2));
+    /// ```
+    pub fn xor(&mut self, other: &Model, offset: (i32, i32, i32)) -> Result<(), &str> {
+        let offset = self.grow_to_fit(other, offset)?;
+        for voxel in other.voxels.iter() {
+            let voxel = moved(voxel, offset);
+            if self.voxels.remove(voxel.position).is_none() {
+                self.voxels.insert(voxel);
+            }
+        }
+        Ok(())
+    }
+
+    //positions in this model where both models have voxels
+    fn overlapping<'a>(
+        &'a self,
+        other: &'a Model,
+        offset: (i32, i32, i32),
+    ) -> impl Iterator<Item = (u8, u8, u8)> + 'a {
+        let size = self.size;
+        other.voxels.iter().filter_map(move |voxel| {
+            //i64 so offsets far away do not overflow
+            let (x, y, z) = (
+                voxel.position.0 as i64 + offset.0 as i64,
+                voxel.position.1 as i64 + offset.1 as i64,
+                voxel.position.2 as i64 + offset.2 as i64,
+            );
+            let inside = x >= 0
+                && y >= 0
+                && z >= 0
+                && x < size.0 as i64
+                && y < size.1 as i64
+                && z < size.2 as i64;
+            let position = (x as u8, y as u8, z as u8);
+            if inside && self.voxels.get(position).is_some() {
+                Some(position)
+            } else {
+                None
+            }
+        })
+    }
+
+    //makes the model large enough for the other model and returns the offset of the other model
+    //after the voxels of this model are moved. The position is changed so the voxels stay in the
+    //same place in the scene.
+    fn grow_to_fit(
+        &mut self,
+        other: &Model,
+        offset: (i32, i32, i32),
+    ) -> Result<(i32, i32, i32), &'static str> {
+        //i64 so offsets far away do not overflow before the size is checked
+        let axis = |offset: i32, size: u16, other_size: u16| {
+            let low = (offset as i64).min(0);
+            let high = (offset as i64 + other_size as i64).max(size as i64);
+            (high - low, -low)
+        };
+        let axes = [
+            axis(offset.0, self.size.0, other.size.0),
+            axis(offset.1, self.size.1, other.size.1),
+            axis(offset.2, self.size.2, other.size.2),
+        ];
+        if axes.iter().any(|(size, _)| *size > 256) {
+            return Err("Models together are larger than 256");
+        }
+        //the shift is at most 256 so it fits in an i32
+        let size = (axes[0].0 as u16, axes[1].0 as u16, axes[2].0 as u16);
+        let shift = (axes[0].1 as i32, axes[1].1 as i32, axes[2].1 as i32);
+        if size == self.size && shift == (0, 0, 0) {
+            return Ok(offset);
+        }
+
+        self.keep_in_scene(size, shift);
+        self.voxels.change(
+            |voxel| {
+                voxel.position = (
+                    (voxel.position.0 as i32 + shift.0) as u8,
+                    (voxel.position.1 as i32 + shift.1) as u8,
+                    (voxel.position.2 as i32 + shift.2) as u8,
+                )
+            },
+            size,
+        );
+        self.size = size;
+        Ok((offset.0 + shift.0, offset.1 + shift.1, offset.2 + shift.2))
+    }
+
+    //changes the position for a model that is changing to the new size with its voxels moved by
+    //the shift, so the voxels stay in the same place in the scene
+    pub(crate) fn keep_in_scene(&mut self, size: (u16, u16, u16), shift: (i32, i32, i32)) {
+        if let Some(position) = self.position {
+            let center = |old: u16, new: u16, shift: i32| new as i32 / 2 - old as i32 / 2 - shift;
+            let change = (
+                center(self.size.0, size.0, shift.0),
+                center(self.size.1, size.1, shift.1),
+                center(self.size.2, size.2, shift.2),
+            );
+            let change = match self.rotation {
+                Some(rotation) => rotation.apply(change),
+                None => change,
+            };
+            self.position = Some((
+                position.0 + change.0,
+                position.1 + change.1,
+                position.2 + change.2,
+            ));
+        }
+    }
+}
+
+fn moved(voxel: &Voxel, offset: (i32, i32, i32)) -> Voxel {
+    Voxel::new(
+        (voxel.position.0 as i32 + offset.0) as u8,
+        (voxel.position.1 as i32 + offset.1) as u8,
+        (voxel.position.2 as i32 + offset.2) as u8,
+        voxel.colorindex,
+    )
+}
@@ -0,0 +1,425 @@
+use crate::model::Model;
+use crate::shapes::{add, length, scale, sub};
+use crate::voxel::Voxel;
+
+//lines and curves are drawn by stamping a ball of voxels along them. Voxels outside the model
+//are left out, so the only error comes from the place mode.
+impl Model {
+    /// Draws a line between two positions. A thickness of 1 is one voxel wide and thicker lines
+    /// are drawn with a ball of that width. Even thicknesses are the odd one below made one voxel
+    /// wider towards positive x, y and z. The positions can be outside the model and only the
+    /// part of the line inside it is drawn.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(20, 20, 20);
+    /// model.add_line((0, 0, 0), (19, 9, 4), 1, 1).unwrap();
+    /// assert_eq!(model.num_of_voxels(), 20);
+    /// assert!(model.is_voxel_at_pos(19, 9, 4));
+    ///
+    /// //a thick cable going out of the model
+    /// model.add_line((10, -5, 10), (10, 30, 10), 2, 3).unwrap();
+    /// assert!(model.is_voxel_at_pos(11, 19, 10));
+    /// ```
+    pub fn add_line(
+        &mut self,
+        from: (i32, i32, i32),
+        to: (i32, i32, i32),
+        colorindex: u8,
+        thickness: u8,
+    ) -> Result<(), &str> {
+        self.add_polyline(&[from, to], colorindex, thickness)
+    }
+
+    /// Draws lines between each position and the next one.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(20, 20, 20);
+    /// //a road with a corner
+    /// model.add_polyline(&[(0, 5, 0), (10, 5, 0), (10, 19, 0)], 1, 3).unwrap();
+    /// assert!(model.is_voxel_at_pos(10, 12, 0));
+    /// ```
+    pub fn add_polyline(
+        &mut self,
+        points: &[(i32, i32, i32)],
+        colorindex: u8,
+        thickness: u8,
+    ) -> Result<(), &str> {
+        self.add_paths(
+            &[points.to_vec()],
+            colorindex,
+            &thickness_offsets(thickness, self.size),
+        )
+    }
+
+    /// Draws a cubic Bezier curve as a tube. The curve starts at the first point and ends at the
+    /// last one, with the two points in the middle pulling it towards them. A radius of 0 is one
+    /// voxel wide and the radius is made smaller if it is larger than the model. It will return
+    /// an error if any point is not finite.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(40, 40, 20);
+    /// let points = [(2.0, 2.0, 2.0), (2.0, 30.0, 2.0), (30.0, 30.0, 18.0), (37.0, 37.0, 10.0)];
+    /// model.add_bezier(points, 1, 1.5).unwrap();
+    /// assert!(model.is_voxel_at_pos(2, 2, 2));
+    /// assert!(model.is_voxel_at_pos(37, 37, 10));
+    /// ```
+    pub fn add_bezier(
+        &mut self,
+        points: [(f32, f32, f32); 4],
+        colorindex: u8,
+        radius: f32,
+    ) -> Result<(), &str> {
+        self.add_curves(&[points], colorindex, radius)
+    }
+
+    /// Draws a Catmull-Rom spline as a tube. The spline goes through all of the points. The
+    /// radius works like it does for add_bezier().
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(40, 40, 10);
+    /// let points = [(2.0, 2.0, 5.0), (20.0, 10.0, 5.0), (10.0, 30.0, 5.0), (37.0, 37.0, 5.0)];
+    /// model.add_catmull_rom(&points, 1, 1.0).unwrap();
+    /// assert!(model.is_voxel_at_pos(20, 10, 5));
+    /// assert!(model.is_voxel_at_pos(10, 30, 5));
+    /// ```
+    pub fn add_catmull_rom(
+        &mut self,
+        points: &[(f32, f32, f32)],
+        colorindex: u8,
+        radius: f32,
+    ) -> Result<(), &str> {
+        //each part of the spline is the same as a Bezier curve between two of the points
+        let mut curves = Vec::new();
+        if let [point] = points {
+            curves.push([*point; 4]);
+        }
+        for i in 1..points.len() {
+            //the ends are repeated so the spline reaches them
+            let p0 = points[i.saturating_sub(2)];
+            let p1 = points[i - 1];
+            let p2 = points[i];
+            let p3 = points[(i + 1).min(points.len() - 1)];
+            curves.push([
+                p1,
+                add(p1, scale(sub(p2, p0), 1.0 / 6.0)),
+                sub(p2, scale(sub(p3, p1), 1.0 / 6.0)),
+                p2,
+            ]);
+        }
+
+        self.add_curves(&curves, colorindex, radius)
+    }
+
+    //draws cubic Bezier curves. Only the pieces of the curves that can reach the model are
+    //stepped through so far away points do not use a lot of memory.
+    fn add_curves(
+        &mut self,
+        curves: &[[(f32, f32, f32); 4]],
+        colorindex: u8,
+        radius: f32,
+    ) -> Result<(), &'static str> {
+        let finite = curves
+            .iter()
+            .flatten()
+            .all(|point| point.0.is_finite() && point.1.is_finite() && point.2.is_finite());
+        if !finite {
+            return Err("Curve points must be finite");
+        }
+
+        let ball = ball_offsets(radius, self.size);
+        let (min, max) = self.reach_box(&ball);
+        //points are rounded so pieces up to half a voxel outside can still reach
+        let min = [
+            min[0] as f32 - 1.0,
+            min[1] as f32 - 1.0,
+            min[2] as f32 - 1.0,
+        ];
+        let max = [
+            max[0] as f32 + 1.0,
+            max[1] as f32 + 1.0,
+            max[2] as f32 + 1.0,
+        ];
+
+        let paths: Vec<Vec<(i32, i32, i32)>> = curves
+            .iter()
+            .flat_map(|curve| curve_pieces(*curve, min, max))
+            .map(|piece| {
+                let [p0, p1, p2, p3] = piece;
+                let steps = curve_steps(&piece);
+                (0..=steps)
+                    .map(|step| {
+                        let t = step as f32 / steps as f32;
+                        let u = 1.0 - t;
+                        round(sum(&[
+                            scale(p0, u * u * u),
+                            scale(p1, 3.0 * u * u * t),
+                            scale(p2, 3.0 * u * t * t),
+                            scale(p3, t * t * t),
+                        ]))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.add_paths(&paths, colorindex, &ball)
+    }
+
+    //box of the positions that are close enough to the model for the ball to reach it
+    fn reach_box(&self, ball: &[(i32, i32, i32)]) -> ([i64; 3], [i64; 3]) {
+        let reach = ball
+            .iter()
+            .map(|&(x, y, z)| x.abs().max(y.abs()).max(z.abs()))
+            .max()
+            .unwrap_or(0) as i64;
+        let size = self.size;
+        (
+            [-reach; 3],
+            [
+                size.0 as i64 - 1 + reach,
+                size.1 as i64 - 1 + reach,
+                size.2 as i64 - 1 + reach,
+            ],
+        )
+    }
+
+    //draws lines between the points of each path with the ball of offsets at every voxel of
+    //the lines
+    fn add_paths(
+        &mut self,
+        paths: &[Vec<(i32, i32, i32)>],
+        colorindex: u8,
+        ball: &[(i32, i32, i32)],
+    ) -> Result<(), &'static str> {
+        //only the parts of the lines that are close enough for the ball to reach the model
+        let (min, max) = self.reach_box(ball);
+        let size = self.size;
+
+        let lines = paths.iter().flat_map(|points| match &points[..] {
+            //a single point is drawn as a line to itself
+            [point] => vec![(*point, *point)],
+            _ => points.windows(2).map(|pair| (pair[0], pair[1])).collect(),
+        });
+        let mut positions: Vec<(u8, u8, u8)> = lines
+            .flat_map(|(from, to)| clipped_line(from, to, min, max))
+            .flat_map(|point| {
+                ball.iter()
+                    .map(move |offset| {
+                        (
+                            point.0 + offset.0 as i64,
+                            point.1 + offset.1 as i64,
+                            point.2 + offset.2 as i64,
+                        )
+                    })
+                    .filter(|&(x, y, z)| {
+                        x >= 0
+                            && y >= 0
+                            && z >= 0
+                            && x < size.0 as i64
+                            && y < size.1 as i64
+                            && z < size.2 as i64
+                    })
+                    .map(|(x, y, z)| (x as u8, y as u8, z as u8))
+            })
+            .collect();
+        //each voxel is only placed once so the place mode does not see the curve overlap itself
+        positions.sort_unstable();
+        positions.dedup();
+
+        self.place_voxels(
+            positions
+                .into_iter()
+                .map(|(x, y, z)| Voxel::new(x, y, z, colorindex)),
+        )
+    }
+}
+
+//voxels of a line with each one touching the one before it. Only the voxels between min and max
+//are returned. Each voxel is worked out from how many steps it is along the line, so the line is
+//clipped before stepping through it and far away positions do not make it slow.
+fn clipped_line(
+    from: (i32, i32, i32),
+    to: (i32, i32, i32),
+    min: [i64; 3],
+    max: [i64; 3],
+) -> impl Iterator<Item = (i64, i64, i64)> {
+    let from = [from.0 as i64, from.1 as i64, from.2 as i64];
+    let to = [to.0 as i64, to.1 as i64, to.2 as i64];
+    let delta = [
+        (to[0] - from[0]).abs(),
+        (to[1] - from[1]).abs(),
+        (to[2] - from[2]).abs(),
+    ];
+    let step = [
+        (to[0] - from[0]).signum(),
+        (to[1] - from[1]).signum(),
+        (to[2] - from[2]).signum(),
+    ];
+    let longest = *delta.iter().max().unwrap();
+
+    //the same as stepping along the longest axis and moving on the other axes each time the
+    //error, which starts at half of the longest delta, goes below 0
+    let point = move |i: i64, axis: usize| -> i64 {
+        if longest == 0 {
+            return from[axis];
+        }
+        let moved = (i as i128 * delta[axis] as i128 - (longest / 2) as i128 + longest as i128 - 1)
+            .div_euclid(longest as i128);
+        from[axis] + step[axis] * moved as i64
+    };
+
+    //each axis only moves one way so the steps inside the box are one range
+    let mut first = 0;
+    let mut last = longest;
+    for axis in 0..3 {
+        let (enter, leave) = if step[axis] >= 0 {
+            (
+                first_step(longest, |i| point(i, axis) >= min[axis]),
+                first_step(longest, |i| point(i, axis) > max[axis]),
+            )
+        } else {
+            (
+                first_step(longest, |i| point(i, axis) <= max[axis]),
+                first_step(longest, |i| point(i, axis) < min[axis]),
+            )
+        };
+        first = first.max(enter);
+        last = last.min(leave - 1);
+    }
+
+    (first..=last).map(move |i| (point(i, 0), point(i, 1), point(i, 2)))
+}
+
+//first step from 0 to last that the condition is true for, or last + 1 if there is none. Once the
+//condition is true it must stay true.
+fn first_step<T>(last: i64, condition: T) -> i64
+where
+    T: Fn(i64) -> bool,
+{
+    let (mut low, mut high) = (0, last + 1);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if condition(middle) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    low
+}
+
+//offsets of the ball used to draw a line with the thickness. Even thicknesses are the ball of the
+//odd thickness below moved by one voxel on each axis.
+fn thickness_offsets(thickness: u8, size: (u16, u16, u16)) -> Vec<(i32, i32, i32)> {
+    let ball = ball_offsets((thickness.saturating_sub(1) / 2) as f32, size);
+    if thickness < 2 || thickness % 2 == 1 {
+        return ball;
+    }
+
+    let mut offsets: Vec<(i32, i32, i32)> = ball
+        .iter()
+        .flat_map(|&(x, y, z)| {
+            (0..8).map(move |corner| (x + (corner & 1), y + (corner >> 1 & 1), z + (corner >> 2)))
+        })
+        .collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    offsets
+}
+
+//all offsets that are inside a ball with the radius. The radius is made no larger than the
+//largest side of the model so there are not more offsets than the model could hold.
+fn ball_offsets(radius: f32, size: (u16, u16, u16)) -> Vec<(i32, i32, i32)> {
+    let radius = radius.min(size.0.max(size.1).max(size.2) as f32);
+    let reach = radius.max(0.0).floor() as i32;
+    let mut offsets = Vec::new();
+    for x in -reach..=reach {
+        for y in -reach..=reach {
+            for z in -reach..=reach {
+                if ((x * x + y * y + z * z) as f32).sqrt() <= radius.max(0.0) {
+                    offsets.push((x, y, z));
+                }
+            }
+        }
+    }
+    offsets
+}
+
+//pieces longer than this are split before they are stepped through
+const PIECE_LENGTH: f32 = 64.0;
+//pieces are not split more times than this
+const MAX_SPLITS: u32 = 48;
+
+//splits the curve in half until the pieces are short, leaving out pieces that can not be inside
+//the box. A piece of a curve is always inside the box around its four points.
+fn curve_pieces(
+    curve: [(f32, f32, f32); 4],
+    min: [f32; 3],
+    max: [f32; 3],
+) -> Vec<[(f32, f32, f32); 4]> {
+    let mut pieces = Vec::new();
+    let mut stack = vec![(curve, 0)];
+    while let Some((curve, splits)) = stack.pop() {
+        let outside = (0..3).any(|axis| {
+            let values = curve.iter().map(|point| [point.0, point.1, point.2][axis]);
+            values.clone().fold(f32::INFINITY, f32::min) > max[axis]
+                || values.fold(f32::NEG_INFINITY, f32::max) < min[axis]
+        });
+        if outside {
+            continue;
+        }
+        if splits == MAX_SPLITS || curve_length(&curve) <= PIECE_LENGTH {
+            pieces.push(curve);
+            continue;
+        }
+
+        let [p0, p1, p2, p3] = curve;
+        let (a, b, c) = (middle(p0, p1), middle(p1, p2), middle(p2, p3));
+        let (d, e) = (middle(a, b), middle(b, c));
+        let center = middle(d, e);
+        //the first half is put on top so the pieces are in order
+        stack.push(([center, e, c, p3], splits + 1));
+        stack.push(([p0, a, d, center], splits + 1));
+    }
+    pieces
+}
+
+//each point is halved first so large points do not add up to infinity
+fn middle(a: (f32, f32, f32), b: (f32, f32, f32)) -> (f32, f32, f32) {
+    add(scale(a, 0.5), scale(b, 0.5))
+}
+
+//length of the lines between the points, which is never shorter than the curve
+fn curve_length(points: &[(f32, f32, f32)]) -> f32 {
+    points
+        .windows(2)
+        .map(|pair| length(sub(pair[1], pair[0])))
+        .sum()
+}
+
+//number of steps so a curve has about two points for every voxel along it. Pieces that could not
+//be split any more are stepped through less often and the lines between the points fill them in.
+fn curve_steps(points: &[(f32, f32, f32)]) -> usize {
+    (curve_length(points).min(PIECE_LENGTH).ceil() as usize * 2).max(1)
+}
+
+fn sum(points: &[(f32, f32, f32)]) -> (f32, f32, f32) {
+    points
+        .iter()
+        .fold((0.0, 0.0, 0.0), |total, point| add(total, *point))
+}
+
+fn round(a: (f32, f32, f32)) -> (i32, i32, i32) {
+    (a.0.round() as i32, a.1.round() as i32, a.2.round() as i32)
+}
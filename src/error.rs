@@ -0,0 +1,103 @@
+use std::fmt;
+use std::io;
+
+/// Error returned when a .vox file can not be read or written.
+#[derive(Debug)]
+pub enum VoxError {
+    /// The underlying reader or writer failed.
+    Io(io::Error),
+    /// The data ended while more bytes were expected at this offset.
+    UnexpectedEof { offset: usize },
+    /// The file does not start with `VOX `.
+    BadMagic { found: [u8; 4] },
+    /// The file has a version with a major version (the hundreds digit) that is not known.
+    UnsupportedVersion(u32),
+    /// A chunk that is required was not found.
+    MissingChunk(&'static str),
+    /// A dictionary, or a value inside one, could not be parsed.
+    InvalidDict { offset: usize },
+    /// A string was not valid UTF-8.
+    InvalidUtf8 { offset: usize },
+    /// A voxel used color index 0, which is reserved for empty space.
+    InvalidColorIndex { offset: usize },
+    /// A chunk has a value that is out of range, or is inside too many other chunks.
+    InvalidChunk { offset: usize },
+    /// A scene graph node refers to a node that does not exist or that has already been used,
+    /// or the scene graph is too deep.
+    InvalidNode { offset: usize },
+    /// A line of an OBJ, MTL or ASCII STL file could not be parsed, or a vertex was not a finite
+    /// number. For binary STL files the line is the number of the triangle. Lines start at 1.
+    InvalidMesh { line: usize },
+}
+
+impl VoxError {
+    //moves the offset for errors that were made while reading part of the data
+    pub(crate) fn shift_offset(self, by: usize) -> VoxError {
+        match self {
+            VoxError::UnexpectedEof { offset } => VoxError::UnexpectedEof {
+                offset: offset + by,
+            },
+            VoxError::InvalidDict { offset } => VoxError::InvalidDict {
+                offset: offset + by,
+            },
+            VoxError::InvalidUtf8 { offset } => VoxError::InvalidUtf8 {
+                offset: offset + by,
+            },
+            VoxError::InvalidColorIndex { offset } => VoxError::InvalidColorIndex {
+                offset: offset + by,
+            },
+            VoxError::InvalidChunk { offset } => VoxError::InvalidChunk {
+                offset: offset + by,
+            },
+            VoxError::InvalidNode { offset } => VoxError::InvalidNode {
+                offset: offset + by,
+            },
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for VoxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VoxError::Io(err) => write!(f, "io error: {}", err),
+            VoxError::UnexpectedEof { offset } => {
+                write!(f, "unexpected end of data at byte {}", offset)
+            }
+            VoxError::BadMagic { found } => {
+                write!(f, "expected file to start with \"VOX \", found {:?}", found)
+            }
+            VoxError::UnsupportedVersion(version) => {
+                write!(f, "unsupported .vox file version {}", version)
+            }
+            VoxError::MissingChunk(id) => write!(f, "missing {} chunk", id),
+            VoxError::InvalidDict { offset } => {
+                write!(f, "invalid dictionary in chunk at byte {}", offset)
+            }
+            VoxError::InvalidUtf8 { offset } => write!(f, "invalid utf-8 at byte {}", offset),
+            VoxError::InvalidColorIndex { offset } => {
+                write!(f, "voxel at byte {} has color index 0", offset)
+            }
+            VoxError::InvalidChunk { offset } => write!(f, "invalid chunk at byte {}", offset),
+            VoxError::InvalidNode { offset } => {
+                write!(f, "invalid scene graph node at byte {}", offset)
+            }
+            VoxError::InvalidMesh { line } => write!(f, "invalid mesh data on line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for VoxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VoxError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for VoxError {
+    fn from(err: io::Error) -> VoxError {
+        VoxError::Io(err)
+    }
+}
@@ -0,0 +1,71 @@
+use crate::node::{Node, NodeExtras, NodeTransform, NodeType};
+use crate::rotation::Rotation;
+use crate::transform::{Keyframe, Transform};
+
+/// A group in the scene. Groups can hold models, copies of models and other groups. Moving,
+/// rotating or hiding a group does the same to everything inside it.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub position: Option<(i32, i32, i32)>,
+    pub rotation: Option<Rotation>,
+    pub layer: Option<i32>,
+    pub name: Option<String>,
+    pub hidden: Option<bool>,
+    /// Keyframes of the animation after the first frame, with the frame index they start at.
+    pub frames: Vec<(u32, Keyframe)>,
+    pub(crate) id: i32,
+    //id of the group this group is in. None is the root group.
+    pub(crate) parent: Option<i32>,
+    pub(crate) extras: NodeExtras,
+}
+
+impl Group {
+    pub(crate) fn new(id: i32, parent: Option<i32>, transform: Transform) -> Group {
+        Group {
+            position: transform.translation,
+            rotation: transform.rotation,
+            layer: transform.layer,
+            name: transform.name,
+            hidden: transform.hidden,
+            frames: vec![],
+            id,
+            parent,
+            extras: NodeExtras::default(),
+        }
+    }
+
+    /// Returns where the group is placed and how it is shown.
+    pub fn transform(&self) -> Transform {
+        Transform {
+            translation: self.position,
+            rotation: self.rotation,
+            layer: self.layer,
+            name: self.name.clone(),
+            hidden: self.hidden,
+        }
+    }
+
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    /// Returns the id of the group this group is in. None is the root of the scene.
+    pub fn parent(&self) -> Option<i32> {
+        self.parent
+    }
+
+    //the transform node and group node without any children in the group
+    pub(crate) fn to_node(&self) -> Node {
+        self.extras.to_node(
+            self.name.clone(),
+            self.hidden,
+            self.transform_data(),
+            NodeType::Group,
+        )
+    }
+
+    pub(crate) fn transform_data(&self) -> NodeTransform {
+        self.extras
+            .transform(self.layer, self.rotation, self.position, &self.frames)
+    }
+}
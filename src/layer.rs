@@ -1,50 +1,89 @@
+use crate::color::Color;
+use crate::error::VoxError;
 use crate::node::bool_to_string;
-use crate::riff::{Dict, VoxString, LAYR};
-use std::fs::File;
-use std::io::BufWriter;
+use crate::riff::{Dict, LAYR};
+use std::io;
+use std::io::Write;
 
+/// A layer in the scene. Models, copies and groups are put in a layer by its id.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Layer {
     id: i32,
     pub name: Option<String>,
     pub hidden: Option<bool>,
+    /// The color shown for the layer in MagicaVoxel. The alpha is not saved.
+    pub color: Option<Color>,
+    //pairs that are not used by this crate
+    pub(crate) other_attributes: Vec<(String, String)>,
 }
 
 impl Layer {
-    pub fn new(name: String, hidden: bool, id: i32) -> Layer {
+    pub(crate) fn new(name: String, hidden: bool, id: i32) -> Layer {
         Layer {
             id,
             name: Some(name),
             hidden: Some(hidden),
+            color: None,
+            other_attributes: vec![],
         }
     }
-    pub fn from_chunk(chunk: LAYR) -> Layer {
-        Layer {
+
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    pub(crate) fn set_id(&mut self, id: i32) {
+        self.id = id
+    }
+
+    //offset is the start of the chunk and is used for errors
+    pub(crate) fn from_chunk(chunk: LAYR, offset: usize) -> Result<Layer, VoxError> {
+        Ok(Layer {
             id: chunk.layer_id,
             name: Layer::has_name(&chunk),
-            hidden: Layer::is_hidden(&chunk),
+            hidden: Layer::is_hidden(&chunk, offset)?,
+            color: Layer::has_color(&chunk, offset)?,
+            other_attributes: chunk
+                .layer_attributes
+                .other_pairs(&["_name", "_hidden", "_color"]),
+        })
+    }
+
+    pub(crate) fn is_hidden(chunk: &LAYR, offset: usize) -> Result<Option<bool>, VoxError> {
+        for pair in chunk.layer_attributes.pairs.iter() {
+            if pair.0.content == "_hidden" {
+                return match pair.1.content.parse::<i32>() {
+                    Ok(value) => Ok(Some(value == 1)),
+                    Err(_) => Err(VoxError::InvalidDict { offset }),
+                };
+            }
         }
+
+        Ok(None)
     }
 
-    pub(crate) fn is_hidden(chunk: &LAYR) -> Option<bool> {
-        if !chunk.layer_attributes.pairs.is_empty() {
-            for pair in chunk.layer_attributes.pairs.iter() {
-                if pair.0.content == *"_hidden" {
-                    println!("here");
-                    return Some(
-                        pair.1
-                            .content
-                            .parse::<i32>()
-                            .expect("failed to parse value")
-                            == 1,
-                    );
-                }
+    //the color is written as "r g b"
+    pub(crate) fn has_color(chunk: &LAYR, offset: usize) -> Result<Option<Color>, VoxError> {
+        for pair in chunk.layer_attributes.pairs.iter() {
+            if pair.0.content == "_color" {
+                let values: Vec<u8> = pair
+                    .1
+                    .content
+                    .split(' ')
+                    .map(|value| value.parse::<u8>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| VoxError::InvalidDict { offset })?;
+                return match values[..] {
+                    [r, g, b] => Ok(Some(Color::new(r, g, b, 255))),
+                    _ => Err(VoxError::InvalidDict { offset }),
+                };
             }
         }
 
-        None
+        Ok(None)
     }
 
-    pub fn has_name(chunk: &LAYR) -> Option<String> {
+    pub(crate) fn has_name(chunk: &LAYR) -> Option<String> {
         if !chunk.layer_attributes.pairs.is_empty() {
             for pair in chunk.layer_attributes.pairs.iter() {
                 if pair.0.content == *"_name" {
@@ -56,7 +95,7 @@ impl Layer {
         None
     }
 
-    pub fn to_chunk(&self) -> LAYR {
+    pub(crate) fn to_chunk(&self) -> LAYR {
         LAYR {
             layer_id: self.id,
             layer_attributes: self.to_dict(),
@@ -64,45 +103,34 @@ impl Layer {
         }
     }
 
-    pub fn to_dict(&self) -> Dict {
-        let num_pairs: i32;
-        if self.hidden.is_some() && self.name.is_some() {
-            num_pairs = 2;
-        } else if self.hidden.is_some() || self.name.is_some() {
-            num_pairs = 1;
-        } else {
-            num_pairs = 0;
-        }
+    pub(crate) fn to_dict(&self) -> Dict {
+        let mut pairs = Vec::new();
 
-        let mut pairs: Vec<(VoxString, VoxString)> = Vec::new();
+        if let Some(name) = &self.name {
+            pairs.push((String::from("_name"), name.clone()));
+        }
 
-        if self.name.is_some() {
-            pairs.push((
-                VoxString::new(5, String::from("_name")),
-                VoxString::new(
-                    self.name.as_ref().unwrap().len() as i32,
-                    self.name.as_ref().unwrap().clone(),
-                ),
-            ));
+        if let Some(hidden) = self.hidden {
+            pairs.push((String::from("_hidden"), bool_to_string(hidden)));
         }
 
-        if self.hidden.is_some() {
+        if let Some(color) = self.color {
             pairs.push((
-                VoxString::new(7, String::from("_hidden")),
-                VoxString::new(1, bool_to_string(self.hidden.unwrap())),
+                String::from("_color"),
+                format!("{} {} {}", color.r, color.g, color.b),
             ));
         }
-        Dict {
-            num_of_pairs: num_pairs,
-            pairs,
-        }
+
+        pairs.extend(self.other_attributes.iter().cloned());
+        Dict::new(pairs)
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        self.to_chunk().write(buf_writer);
+    pub(crate) fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        self.to_chunk().write(buf_writer)
     }
 
-    pub fn get_size(&self) -> i32 {
-        self.to_chunk().get_size()
+    //size in bytes when written including the chunk header
+    pub(crate) fn get_size(&self) -> i32 {
+        12 + self.to_chunk().get_size()
     }
 }
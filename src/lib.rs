@@ -1,18 +1,47 @@
+mod axis;
+mod chunk;
 mod color;
 mod convert;
 mod copy;
+mod csg;
+mod curves;
+mod error;
+mod group;
 mod layer;
+mod material;
+mod mesh;
 mod model;
 #[allow(dead_code)]
 mod node;
+mod orient;
+mod placemode;
+mod render;
+mod resample;
 #[allow(dead_code)]
 mod riff;
+mod rotation;
+pub mod sdf;
+mod shapes;
+mod storage;
+mod transform;
 mod voxel;
 mod voxfile;
 mod writing;
 
+pub use axis::{Axis, Overflow};
+pub use chunk::{Chunk, ChunkReader};
 pub use color::*;
+pub use copy::ModelCopy;
+pub use error::VoxError;
+pub use group::Group;
+pub use layer::Layer;
+pub use material::{Material, MaterialType, MediaType};
+pub use mesh::{FillMode, Mesh, Triangle};
 pub use model::Model;
-use std::io::Write;
+pub use placemode::PlaceMode;
+pub use render::{Camera, CameraMode, RenderObject};
+pub use rotation::Rotation;
+pub use storage::{Storage, VoxelsMut};
+pub use transform::{Keyframe, Transform};
 pub use voxel::*;
 pub use voxfile::VoxFile;
@@ -0,0 +1,271 @@
+use crate::error::VoxError;
+use crate::riff::{Dict, ReadOrder, MATL};
+use std::io;
+use std::io::Write;
+
+/// The kind of material. Written as the `_type` property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaterialType {
+    Diffuse,
+    Metal,
+    Glass,
+    Emit,
+    Blend,
+    Media,
+    Cloud,
+    /// A type not known by this crate, like `_plastic` from older versions of MagicaVoxel. It is
+    /// written back unchanged.
+    Other(String),
+}
+
+impl MaterialType {
+    fn to_str(&self) -> &str {
+        match self {
+            MaterialType::Diffuse => "_diffuse",
+            MaterialType::Metal => "_metal",
+            MaterialType::Glass => "_glass",
+            MaterialType::Emit => "_emit",
+            MaterialType::Blend => "_blend",
+            MaterialType::Media => "_media",
+            MaterialType::Cloud => "_cloud",
+            MaterialType::Other(string) => string,
+        }
+    }
+
+    fn from_str(string: &str) -> MaterialType {
+        match string {
+            "_diffuse" => MaterialType::Diffuse,
+            "_metal" => MaterialType::Metal,
+            "_glass" => MaterialType::Glass,
+            "_emit" => MaterialType::Emit,
+            "_blend" => MaterialType::Blend,
+            "_media" => MaterialType::Media,
+            "_cloud" => MaterialType::Cloud,
+            _ => MaterialType::Other(String::from(string)),
+        }
+    }
+}
+
+/// How light travels through a media material. Written as the `_media` property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaType {
+    Absorb,
+    Scatter,
+    Emit,
+    Subsurface,
+    /// A media type not known by this crate. It is written back unchanged.
+    Other(String),
+}
+
+impl MediaType {
+    fn to_str(&self) -> &str {
+        match self {
+            MediaType::Absorb => "_absorb",
+            MediaType::Scatter => "_scatter",
+            MediaType::Emit => "_emit",
+            MediaType::Subsurface => "_sss",
+            MediaType::Other(string) => string,
+        }
+    }
+
+    fn from_str(string: &str) -> MediaType {
+        match string {
+            "_absorb" => MediaType::Absorb,
+            "_scatter" => MediaType::Scatter,
+            "_emit" => MediaType::Emit,
+            "_sss" => MediaType::Subsurface,
+            _ => MediaType::Other(String::from(string)),
+        }
+    }
+}
+
+/// Material for an index on the palette. Properties that are None are not written.
+#[derive(Debug, Clone)]
+pub struct Material {
+    pub material_type: MaterialType,
+    /// `_weight` from 0 to 1
+    pub weight: Option<f32>,
+    /// `_rough`
+    pub roughness: Option<f32>,
+    /// `_spec`
+    pub specular: Option<f32>,
+    /// `_ior` index of refraction
+    pub ior: Option<f32>,
+    /// `_att`
+    pub attenuation: Option<f32>,
+    /// `_flux`
+    pub flux: Option<f32>,
+    /// `_emit`
+    pub emission: Option<f32>,
+    /// `_ldr`
+    pub ldr: Option<f32>,
+    /// `_metal`
+    pub metalness: Option<f32>,
+    /// `_alpha`
+    pub alpha: Option<f32>,
+    /// `_trans`
+    pub transparency: Option<f32>,
+    /// `_d`
+    pub density: Option<f32>,
+    /// `_g`
+    pub phase: Option<f32>,
+    /// `_media`
+    pub media: Option<MediaType>,
+    //properties that are not used by this crate
+    pub(crate) other_properties: Vec<(String, String)>,
+    pub(crate) read_order: ReadOrder,
+}
+
+impl Material {
+    /// Creates a new material of the type given with no other properties set.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Material, MaterialType};
+    ///
+    /// let mut glass = Material::new(MaterialType::Glass);
+    /// glass.roughness = Some(0.1);
+    /// glass.ior = Some(0.5);
+    /// ```
+    pub fn new(material_type: MaterialType) -> Material {
+        Material {
+            material_type,
+            weight: None,
+            roughness: None,
+            specular: None,
+            ior: None,
+            attenuation: None,
+            flux: None,
+            emission: None,
+            ldr: None,
+            metalness: None,
+            alpha: None,
+            transparency: None,
+            density: None,
+            phase: None,
+            media: None,
+            other_properties: vec![],
+            read_order: ReadOrder::default(),
+        }
+    }
+
+    //offset is the start of the chunk and is used for errors
+    pub(crate) fn from_chunk(chunk: &MATL, offset: usize) -> Result<Material, VoxError> {
+        let mut material = Material::new(MaterialType::Diffuse);
+        material.read_order = ReadOrder::new(&chunk.properties);
+        for (key, value) in chunk.properties.pairs.iter() {
+            let value = value.content.as_str();
+            if key.content == "_type" {
+                material.material_type = MaterialType::from_str(value);
+            } else if key.content == "_media" {
+                material.media = Some(MediaType::from_str(value));
+            } else if let Some(property) = material.float_property(&key.content) {
+                *property = Some(
+                    value
+                        .parse()
+                        .map_err(|_| VoxError::InvalidDict { offset })?,
+                );
+            } else {
+                material
+                    .other_properties
+                    .push((key.content.clone(), String::from(value)));
+            }
+        }
+
+        Ok(material)
+    }
+
+    fn float_property(&mut self, key: &str) -> Option<&mut Option<f32>> {
+        match key {
+            "_weight" => Some(&mut self.weight),
+            "_rough" => Some(&mut self.roughness),
+            "_spec" => Some(&mut self.specular),
+            "_ior" => Some(&mut self.ior),
+            "_att" => Some(&mut self.attenuation),
+            "_flux" => Some(&mut self.flux),
+            "_emit" => Some(&mut self.emission),
+            "_ldr" => Some(&mut self.ldr),
+            "_metal" => Some(&mut self.metalness),
+            "_alpha" => Some(&mut self.alpha),
+            "_trans" => Some(&mut self.transparency),
+            "_d" => Some(&mut self.density),
+            "_g" => Some(&mut self.phase),
+            _ => None,
+        }
+    }
+
+    //the material for index 0 is written with the id 256 like MagicaVoxel does
+    pub(crate) fn to_chunk(&self, id: u8) -> MATL {
+        MATL {
+            material_id: if id == 0 { 256 } else { id as i32 },
+            properties: self.to_dict(),
+        }
+    }
+
+    pub(crate) fn to_dict(&self) -> Dict {
+        let mut pairs = vec![string_pair("_type", self.material_type.to_str())];
+
+        let floats = [
+            ("_weight", self.weight),
+            ("_rough", self.roughness),
+            ("_spec", self.specular),
+            ("_ior", self.ior),
+            ("_att", self.attenuation),
+            ("_flux", self.flux),
+            ("_emit", self.emission),
+            ("_ldr", self.ldr),
+            ("_metal", self.metalness),
+            ("_alpha", self.alpha),
+            ("_trans", self.transparency),
+            ("_d", self.density),
+            ("_g", self.phase),
+        ];
+        for (key, value) in floats.iter() {
+            if let Some(value) = value {
+                pairs.push(string_pair(key, &value.to_string()));
+            }
+        }
+
+        if let Some(media) = &self.media {
+            pairs.push(string_pair("_media", media.to_str()));
+        }
+
+        pairs.extend(self.other_properties.iter().cloned());
+        Dict::new(self.read_order.apply(pairs))
+    }
+
+    pub(crate) fn write<W: Write>(&self, id: u8, buf_writer: &mut W) -> io::Result<()> {
+        self.to_chunk(id).write(buf_writer)
+    }
+
+    //size in bytes when written including the chunk header
+    pub(crate) fn get_size(&self) -> i32 {
+        12 + self.to_chunk(0).get_size()
+    }
+}
+
+//the order properties were read in is not compared
+impl PartialEq for Material {
+    fn eq(&self, other: &Material) -> bool {
+        self.material_type == other.material_type
+            && self.weight == other.weight
+            && self.roughness == other.roughness
+            && self.specular == other.specular
+            && self.ior == other.ior
+            && self.attenuation == other.attenuation
+            && self.flux == other.flux
+            && self.emission == other.emission
+            && self.ldr == other.ldr
+            && self.metalness == other.metalness
+            && self.alpha == other.alpha
+            && self.transparency == other.transparency
+            && self.density == other.density
+            && self.phase == other.phase
+            && self.media == other.media
+            && self.other_properties == other.other_properties
+    }
+}
+
+fn string_pair(key: &str, value: &str) -> (String, String) {
+    (String::from(key), String::from(value))
+}
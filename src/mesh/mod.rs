@@ -0,0 +1,348 @@
+use crate::color::Color;
+use crate::model::Model;
+use crate::voxel::Voxel;
+use std::collections::HashMap;
+
+mod obj;
+mod stl;
+
+/// A triangle of a mesh with the color index its voxels get.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangle {
+    pub vertices: [(f32, f32, f32); 3],
+    pub colorindex: u8,
+}
+
+impl Triangle {
+    pub fn new(vertices: [(f32, f32, f32); 3], colorindex: u8) -> Triangle {
+        Triangle {
+            vertices,
+            colorindex,
+        }
+    }
+}
+
+/// How a mesh is turned into voxels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FillMode {
+    /// Only the voxels that the triangles go through are filled.
+    Surface,
+    /// The inside of the mesh is also filled. The mesh needs to be closed for this to work.
+    Solid,
+}
+
+/// Triangles that can be turned into voxels. The z axis is up like in MagicaVoxel.
+///
+/// # Example
+/// ```
+/// use create_vox::{FillMode, Mesh, Model, Triangle};
+///
+/// let mut mesh = Mesh::new();
+/// mesh.triangles.push(Triangle::new([(0.0, 0.0, 0.0), (4.0, 0.0, 0.0), (0.0, 4.0, 0.0)], 1));
+///
+/// let mut model = Model::new(10, 10, 10);
+/// model.add_mesh(&mesh, 1.0, FillMode::Surface).unwrap();
+/// assert!(model.is_voxel_at_pos(2, 2, 0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Mesh {
+    pub triangles: Vec<Triangle>,
+    /// Colors used by the triangles such as from the materials of an OBJ file. The first color is
+    /// for color index 1.
+    pub colors: Vec<Color>,
+}
+
+type Point = (f32, f32, f32);
+
+//color index of each voxel by its position
+pub(crate) type MeshVoxels = HashMap<(u32, u32, u32), u8>;
+
+impl Mesh {
+    pub fn new() -> Mesh {
+        Mesh {
+            triangles: vec![],
+            colors: vec![],
+        }
+    }
+
+    /// Returns the smallest and largest position of the vertices. None is returned if there are
+    /// no triangles.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let mut vertices = self
+            .triangles
+            .iter()
+            .flat_map(|triangle| triangle.vertices.iter());
+        let first = *vertices.next()?;
+        Some(vertices.fold((first, first), |(min, max), v| {
+            (
+                (min.0.min(v.0), min.1.min(v.1), min.2.min(v.2)),
+                (max.0.max(v.0), max.1.max(v.1), max.2.max(v.2)),
+            )
+        }))
+    }
+
+    //returns an error if a vertex or the resolution is not a finite number, or the resolution is
+    //not above 0. Voxelizing needs both to work out where the voxels are.
+    pub(crate) fn check_finite(&self, resolution: f32) -> Result<(), &'static str> {
+        let finite = self
+            .triangles
+            .iter()
+            .flat_map(|triangle| triangle.vertices.iter())
+            .all(|v| v.0.is_finite() && v.1.is_finite() && v.2.is_finite());
+        if finite && resolution.is_finite() && resolution > 0.0 {
+            Ok(())
+        } else {
+            Err("Mesh vertices and resolution must be finite and the resolution above 0")
+        }
+    }
+
+    //number of voxels on each axis of the mesh at the resolution. None is returned if the size
+    //is not a finite number or does not fit in a u32. A mesh without triangles has a size of 0.
+    pub(crate) fn voxel_size(&self, resolution: f32) -> Option<(u32, u32, u32)> {
+        self.check_finite(resolution).ok()?;
+        let (min, max) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return Some((0, 0, 0)),
+        };
+        //f64 so the size can be checked before it is turned into a whole number
+        let axis_size = |min: f32, max: f32| {
+            let size = ((max as f64 - min as f64) * resolution as f64).round() + 1.0;
+            if size.is_finite() && size >= 1.0 && size <= u32::MAX as f64 {
+                Some(size as u32)
+            } else {
+                None
+            }
+        };
+        Some((
+            axis_size(min.0, max.0)?,
+            axis_size(min.1, max.1)?,
+            axis_size(min.2, max.2)?,
+        ))
+    }
+
+    //the voxels of the mesh with the smallest vertex at voxel 0. The resolution is the number of
+    //voxels for each unit of the mesh and the size is from voxel_size(). Voxel centers are on
+    //whole numbers. Triangles with color index 0 are left out as it is empty space.
+    pub(crate) fn voxelize(
+        &self,
+        resolution: f32,
+        mode: FillMode,
+        size: (u32, u32, u32),
+    ) -> MeshVoxels {
+        let min = match self.bounds() {
+            Some((min, _)) => min,
+            None => return HashMap::new(),
+        };
+        let size = [size.0, size.1, size.2];
+        let triangles: Vec<([[f32; 3]; 3], u8)> = self
+            .triangles
+            .iter()
+            .filter(|triangle| triangle.colorindex != 0)
+            .map(|triangle| {
+                let mut vertices = [[0.0; 3]; 3];
+                for (vertex, v) in vertices.iter_mut().zip(triangle.vertices.iter()) {
+                    *vertex = [
+                        (v.0 - min.0) * resolution,
+                        (v.1 - min.1) * resolution,
+                        (v.2 - min.2) * resolution,
+                    ];
+                }
+                (vertices, triangle.colorindex)
+            })
+            .collect();
+
+        let mut voxels = HashMap::new();
+        for (vertices, colorindex) in triangles.iter() {
+            add_surface(&mut voxels, vertices, *colorindex, size);
+        }
+        if mode == FillMode::Solid {
+            fill_inside(&mut voxels, &triangles, size);
+        }
+
+        voxels
+    }
+}
+
+impl Model {
+    /// Turns the mesh into voxels in this model with the smallest vertex of the mesh at 0, 0, 0.
+    /// The resolution is the number of voxels for each unit of the mesh. Triangles with color
+    /// index 0 are skipped. It will return an error if a vertex or the resolution is not a finite
+    /// number or if the mesh does not fit inside the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{FillMode, Mesh, Model};
+    ///
+    /// let stl = "solid tri
+    /// facet normal 0 0 1
+    /// outer loop
+    /// vertex 0 0 0
+    /// vertex 1 0 0
+    /// vertex 0 1 0
+    /// endloop
+    /// endfacet
+    /// endsolid tri";
+    /// let mesh = Mesh::from_stl(stl.as_bytes()).unwrap();
+    /// let mut model = Model::new(20, 20, 20);
+    /// model.add_mesh(&mesh, 10.0, FillMode::Surface).unwrap();
+    /// assert!(model.is_voxel_at_pos(5, 5, 0));
+    /// assert!(model.add_mesh(&mesh, 100.0, FillMode::Surface).is_err());
+    /// ```
+    pub fn add_mesh(&mut self, mesh: &Mesh, resolution: f32, mode: FillMode) -> Result<(), &str> {
+        //the size is checked before any voxels are made
+        mesh.check_finite(resolution)?;
+        let size = match mesh.voxel_size(resolution) {
+            Some(size)
+                if size.0 <= self.size.0 as u32
+                    && size.1 <= self.size.1 as u32
+                    && size.2 <= self.size.2 as u32 =>
+            {
+                size
+            }
+            _ => return Err("Mesh too large for the model"),
+        };
+
+        let mut voxels: Vec<Voxel> = mesh
+            .voxelize(resolution, mode, size)
+            .into_iter()
+            .map(|((x, y, z), colorindex)| Voxel::new(x as u8, y as u8, z as u8, colorindex))
+            .collect();
+        voxels.sort_unstable_by_key(|voxel| voxel.position);
+        self.place_voxels(voxels.into_iter())
+    }
+}
+
+//adds the voxels that the triangle goes through
+fn add_surface(
+    voxels: &mut HashMap<(u32, u32, u32), u8>,
+    vertices: &[[f32; 3]; 3],
+    colorindex: u8,
+    size: [u32; 3],
+) {
+    let mut min = [0; 3];
+    let mut max = [0; 3];
+    for axis in 0..3 {
+        let values = vertices.iter().map(|vertex| vertex[axis]);
+        let low = values.clone().fold(f32::INFINITY, f32::min);
+        let high = values.fold(f32::NEG_INFINITY, f32::max);
+        min[axis] = (low.round() as u32).min(size[axis] - 1);
+        max[axis] = (high.round() as u32).min(size[axis] - 1);
+    }
+
+    for x in min[0]..=max[0] {
+        for y in min[1]..=max[1] {
+            for z in min[2]..=max[2] {
+                if triangle_box_overlap([x as f32, y as f32, z as f32], 0.5, vertices) {
+                    voxels.insert((x, y, z), colorindex);
+                }
+            }
+        }
+    }
+}
+
+//fills the voxels between where a ray going up each column goes in and out of the mesh
+fn fill_inside(
+    voxels: &mut HashMap<(u32, u32, u32), u8>,
+    triangles: &[([[f32; 3]; 3], u8)],
+    size: [u32; 3],
+) {
+    //the rays are moved a little so they do not go through the edges between triangles
+    const NUDGE: (f32, f32) = (0.000_123, 0.000_371);
+    let mut hits: HashMap<(u32, u32), Vec<(f32, u8)>> = HashMap::new();
+    for (vertices, colorindex) in triangles.iter() {
+        let [a, b, c] = *vertices;
+        let area = (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]);
+        if area == 0.0 {
+            continue;
+        }
+        let low = |axis: usize| a[axis].min(b[axis]).min(c[axis]).floor().max(0.0) as u32;
+        let high =
+            |axis: usize| (a[axis].max(b[axis]).max(c[axis]).ceil() as u32).min(size[axis] - 1);
+        for x in low(0)..=high(0) {
+            for y in low(1)..=high(1) {
+                let (px, py) = (x as f32 + NUDGE.0, y as f32 + NUDGE.1);
+                //barycentric coordinates of the ray on the triangle seen from above
+                let u = ((b[0] - px) * (c[1] - py) - (c[0] - px) * (b[1] - py)) / area;
+                let v = ((c[0] - px) * (a[1] - py) - (a[0] - px) * (c[1] - py)) / area;
+                let w = 1.0 - u - v;
+                if u >= 0.0 && v >= 0.0 && w >= 0.0 {
+                    let z = u * a[2] + v * b[2] + w * c[2];
+                    hits.entry((x, y)).or_default().push((z, *colorindex));
+                }
+            }
+        }
+    }
+
+    for ((x, y), mut column) in hits {
+        column.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for pair in column.chunks_exact(2) {
+            let start = pair[0].0.round().max(0.0) as u32;
+            let end = (pair[1].0.round() as u32).min(size[2] - 1);
+            for z in start..=end {
+                voxels.entry((x, y, z)).or_insert(pair[0].1);
+            }
+        }
+    }
+}
+
+//checks if a triangle and a box overlap using the separating axis test
+fn triangle_box_overlap(center: [f32; 3], half: f32, vertices: &[[f32; 3]; 3]) -> bool {
+    let mut v = [[0.0; 3]; 3];
+    for (v, vertex) in v.iter_mut().zip(vertices.iter()) {
+        *v = sub(*vertex, center);
+    }
+
+    //the axes of the box
+    let [a, b, c] = v;
+    for axis in 0..3 {
+        let low = a[axis].min(b[axis]).min(c[axis]);
+        let high = a[axis].max(b[axis]).max(c[axis]);
+        if low > half || high < -half {
+            return false;
+        }
+    }
+
+    let edges = [sub(v[1], v[0]), sub(v[2], v[1]), sub(v[0], v[2])];
+    let separated = |direction: [f32; 3]| {
+        let projections = [
+            dot(direction, v[0]),
+            dot(direction, v[1]),
+            dot(direction, v[2]),
+        ];
+        let low = projections[0].min(projections[1]).min(projections[2]);
+        let high = projections[0].max(projections[1]).max(projections[2]);
+        let radius = half * (direction[0].abs() + direction[1].abs() + direction[2].abs());
+        low > radius || high < -radius
+    };
+
+    //the normal of the triangle and the edges crossed with the axes of the box
+    if separated(cross(edges[0], edges[1])) {
+        return false;
+    }
+    for edge in edges.iter() {
+        for axis in 0..3 {
+            let mut unit = [0.0; 3];
+            unit[axis] = 1.0;
+            if separated(cross(unit, *edge)) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
@@ -0,0 +1,165 @@
+use crate::color::Color;
+use crate::error::VoxError;
+use crate::mesh::{Mesh, Triangle};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+impl Mesh {
+    /// Reads a mesh from the text of an OBJ file. Faces with more than 3 vertices are split into
+    /// triangles. Each material gets a color index in the order it is first used, starting at 1,
+    /// with the diffuse color from the MTL text put in the colors of the mesh.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, Mesh};
+    ///
+    /// let obj = "mtllib box.mtl
+    /// v 0 0 0
+    /// v 1 0 0
+    /// v 1 1 0
+    /// v 0 1 0
+    /// usemtl red
+    /// f 1 2 3 4";
+    /// let mtl = "newmtl red
+    /// Kd 1.0 0.0 0.0";
+    /// let mesh = Mesh::from_obj(obj, Some(mtl)).unwrap();
+    /// assert_eq!(mesh.triangles.len(), 2);
+    /// assert_eq!(mesh.triangles[0].colorindex, 1);
+    /// assert_eq!(mesh.colors, vec![Color::new(255, 0, 0, 255)]);
+    /// ```
+    pub fn from_obj(obj: &str, mtl: Option<&str>) -> Result<Mesh, VoxError> {
+        let materials = match mtl {
+            Some(mtl) => read_mtl(mtl)?,
+            None => HashMap::new(),
+        };
+
+        let mut mesh = Mesh::new();
+        let mut vertices = Vec::new();
+        let mut used_materials: Vec<&str> = Vec::new();
+        let mut colorindex = 1;
+        for (i, line) in obj.lines().enumerate() {
+            let error = VoxError::InvalidMesh { line: i + 1 };
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("v") => {
+                    let values = parse_floats(words, 3).ok_or(error)?;
+                    vertices.push((values[0], values[1], values[2]));
+                }
+                Some("f") => {
+                    let mut face = Vec::new();
+                    for word in words {
+                        face.push(
+                            vertex_index(word, vertices.len())
+                                .ok_or_else(|| VoxError::InvalidMesh { line: i + 1 })?,
+                        );
+                    }
+                    if face.len() < 3 {
+                        return Err(error);
+                    }
+                    for j in 1..face.len() - 1 {
+                        let triangle =
+                            [vertices[face[0]], vertices[face[j]], vertices[face[j + 1]]];
+                        mesh.triangles.push(Triangle::new(triangle, colorindex));
+                    }
+                }
+                Some("usemtl") => {
+                    let name = words.next().ok_or(error)?;
+                    let index = match used_materials.iter().position(|used| *used == name) {
+                        Some(index) => index,
+                        None => {
+                            used_materials.push(name);
+                            if mesh.colors.len() < 255 {
+                                let color = materials.get(name).copied();
+                                mesh.colors
+                                    .push(color.unwrap_or_else(|| Color::new(255, 255, 255, 255)));
+                            }
+                            used_materials.len() - 1
+                        }
+                    };
+                    colorindex = (index + 1).min(255) as u8;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(mesh)
+    }
+
+    /// Loads an OBJ file. The MTL file named in it is loaded from the same folder.
+    pub fn load_obj(path: &str) -> Result<Mesh, VoxError> {
+        let obj = fs::read_to_string(path)?;
+        let mtl_name = obj
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("mtllib "))
+            .map(|name| name.trim());
+        let mtl = match mtl_name {
+            Some(name) => {
+                let folder = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+                Some(fs::read_to_string(folder.join(name))?)
+            }
+            None => None,
+        };
+
+        Mesh::from_obj(&obj, mtl.as_deref())
+    }
+}
+
+//the diffuse color of each material
+fn read_mtl(mtl: &str) -> Result<HashMap<&str, Color>, VoxError> {
+    let mut materials = HashMap::new();
+    let mut name = None;
+    for (i, line) in mtl.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("newmtl") => name = words.next(),
+            Some("Kd") => {
+                let values = parse_floats(words, 3).ok_or(VoxError::InvalidMesh { line: i + 1 })?;
+                let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                if let Some(name) = name {
+                    let color = Color::new(
+                        channel(values[0]),
+                        channel(values[1]),
+                        channel(values[2]),
+                        255,
+                    );
+                    materials.insert(name, color);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(materials)
+}
+
+//reads the first values of the words as floats. Values that are not finite like nan or inf are
+//not accepted.
+pub(crate) fn parse_floats<'a, I>(words: I, count: usize) -> Option<Vec<f32>>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let values: Vec<f32> = words
+        .take(count)
+        .map(|word| word.parse::<f32>().ok().filter(|value| value.is_finite()))
+        .collect::<Option<_>>()?;
+    if values.len() == count {
+        Some(values)
+    } else {
+        None
+    }
+}
+
+//vertices of a face are written as v, v/vt, v//vn or v/vt/vn. Negative indexes count back from
+//the last vertex.
+fn vertex_index(word: &str, num_of_vertices: usize) -> Option<usize> {
+    let index = word.split('/').next()?.parse::<i64>().ok()?;
+    let index = if index < 0 {
+        num_of_vertices as i64 + index
+    } else {
+        index - 1
+    };
+    if index < 0 || index >= num_of_vertices as i64 {
+        return None;
+    }
+    Some(index as usize)
+}
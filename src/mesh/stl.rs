@@ -0,0 +1,94 @@
+use crate::error::VoxError;
+use crate::mesh::obj::parse_floats;
+use crate::mesh::{Mesh, Triangle};
+use crate::riff::{read_bytes, read_u32};
+use std::fs;
+
+impl Mesh {
+    /// Reads a mesh from a binary or ASCII STL file. All triangles get color index 1.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Mesh;
+    ///
+    /// let mut stl = vec![0; 80];
+    /// stl.extend_from_slice(&1u32.to_le_bytes());
+    /// let values = [0.0f32, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    /// for value in values.iter() {
+    ///     stl.extend_from_slice(&value.to_le_bytes());
+    /// }
+    /// stl.extend_from_slice(&[0, 0]);
+    ///
+    /// let mesh = Mesh::from_stl(&stl).unwrap();
+    /// assert_eq!(mesh.triangles[0].vertices[2], (0.0, 1.0, 0.0));
+    /// ```
+    pub fn from_stl(bytes: &[u8]) -> Result<Mesh, VoxError> {
+        //binary files can also start with "solid" so the size is checked first
+        let is_binary = bytes.len() >= 84 && {
+            let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]);
+            84 + count as usize * 50 == bytes.len()
+        };
+        if !is_binary && bytes.starts_with(b"solid") {
+            let text = std::str::from_utf8(bytes).map_err(|err| VoxError::InvalidUtf8 {
+                offset: err.valid_up_to(),
+            })?;
+            return read_ascii(text);
+        }
+
+        let mut cursor = 80;
+        let count = read_u32(bytes, &mut cursor)?;
+        let mut mesh = Mesh::new();
+        for i in 0..count as usize {
+            //the normal is not used
+            cursor += 12;
+            let mut vertices = [(0.0, 0.0, 0.0); 3];
+            for vertex in vertices.iter_mut() {
+                *vertex = (
+                    read_f32(bytes, &mut cursor)?,
+                    read_f32(bytes, &mut cursor)?,
+                    read_f32(bytes, &mut cursor)?,
+                );
+                if !(vertex.0.is_finite() && vertex.1.is_finite() && vertex.2.is_finite()) {
+                    return Err(VoxError::InvalidMesh { line: i + 1 });
+                }
+            }
+            read_bytes(bytes, &mut cursor, 2)?;
+            mesh.triangles.push(Triangle::new(vertices, 1));
+        }
+        Ok(mesh)
+    }
+
+    /// Loads a binary or ASCII STL file.
+    pub fn load_stl(path: &str) -> Result<Mesh, VoxError> {
+        Mesh::from_stl(&fs::read(path)?)
+    }
+}
+
+fn read_ascii(text: &str) -> Result<Mesh, VoxError> {
+    let mut mesh = Mesh::new();
+    let mut vertices = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("vertex") => {
+                let values = parse_floats(words, 3).ok_or(VoxError::InvalidMesh { line: i + 1 })?;
+                vertices.push((values[0], values[1], values[2]));
+            }
+            Some("endloop") => {
+                if vertices.len() != 3 {
+                    return Err(VoxError::InvalidMesh { line: i + 1 });
+                }
+                mesh.triangles
+                    .push(Triangle::new([vertices[0], vertices[1], vertices[2]], 1));
+                vertices.clear();
+            }
+            _ => {}
+        }
+    }
+    Ok(mesh)
+}
+
+fn read_f32(input: &[u8], cursor: &mut usize) -> Result<f32, VoxError> {
+    let bytes = read_bytes(input, cursor, 4)?;
+    Ok(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
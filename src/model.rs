@@ -1,21 +1,35 @@
+use crate::chunk::Chunk;
 use crate::convert::*;
-use crate::node::{Node, NodeAttributes, NodeType, Transform};
+use crate::node::{Node, NodeExtras, NodeTransform};
 use crate::riff::write_chunk;
+use crate::storage::Voxels;
 use crate::writing::*;
 use crate::*;
-use std::fs::File;
-use std::io::BufWriter;
+use std::io;
+use std::io::Write;
 
 /// Holds voxel data
 #[derive(Clone)]
 pub struct Model {
     pub size: (u16, u16, u16),
-    pub(crate) voxels: Vec<Voxel>,
+    pub(crate) voxels: Voxels,
     pub position: Option<(i32, i32, i32)>,
-    pub(crate) rotation: Option<u8>,
+    pub rotation: Option<Rotation>,
     pub layer: Option<i32>,
     pub name: Option<String>,
+    pub hidden: Option<bool>,
+    /// What happens when a voxel is added where there already is one. This is used by all of
+    /// the functions that add voxels except set().
+    pub place_mode: PlaceMode,
+    /// Keyframes of the animation after the first frame, with the frame index they start at.
+    pub frames: Vec<(u32, Keyframe)>,
+    /// Other models shown in the animation, with the frame index they start at. The model
+    /// itself is shown from the first frame.
+    pub model_frames: Vec<(u32, i32)>,
     pub(crate) id: i32,
+    //id of the group the model is in. None is the root group.
+    pub(crate) group: Option<i32>,
+    pub(crate) extras: NodeExtras,
 }
 
 #[allow(unused_variables)]
@@ -35,115 +49,113 @@ impl Model {
     pub fn new(x: u16, y: u16, z: u16) -> Model {
         Model {
             size: (x, y, z),
-            voxels: Vec::new(),
+            voxels: Voxels::new(Storage::default(), (x, y, z)),
             position: None,
             rotation: None,
             layer: None,
             name: None,
+            hidden: None,
+            place_mode: PlaceMode::default(),
+            frames: vec![],
+            model_frames: vec![],
             id: 0,
+            group: None,
+            extras: NodeExtras::default(),
         }
     }
 
-    pub(crate) fn write(&self, writer: &mut BufWriter<File>) {
-        let size_slice: &[u8] = &[
-            u16_to_array(self.size.0)[0],
-            u16_to_array(self.size.0)[1],
-            0,
-            0,
-            u16_to_array(self.size.1)[0],
-            u16_to_array(self.size.1)[1],
-            0,
-            0,
-            u16_to_array(self.size.2)[0],
-            u16_to_array(self.size.2)[1],
-            0,
-            0,
-        ];
-        write_chunk("SIZE", 12, 0, writer);
-        //writes the slice for size
-        write_slice(writer, size_slice);
-
-        write_chunk("XYZI", ((self.voxels.len() as u32) * 4) + 4, 0, writer);
+    pub(crate) fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_chunk("SIZE", 12, 0, writer)?;
+        //writes the size as 3 u32s
+        write_slice(writer, &u32_to_array(self.size.0 as u32))?;
+        write_slice(writer, &u32_to_array(self.size.1 as u32))?;
+        write_slice(writer, &u32_to_array(self.size.2 as u32))?;
+
+        write_chunk("XYZI", ((self.voxels.len() as u32) * 4) + 4, 0, writer)?;
         //number voxels in the voxobject
-        write_slice(writer, &u32_to_array(self.voxels.len() as u32));
+        write_slice(writer, &u32_to_array(self.voxels.len() as u32))?;
         //writes all of the voxels
-        self.write_voxels(writer);
+        self.write_voxels(writer)
     }
 
-    fn write_voxels(&self, buf_writer: &mut BufWriter<File>) {
-        let mut voxel_slice: Box<Vec<u8>> = Box::new(vec![]);
-        for i in 0..self.voxels.len() {
-            voxel_slice.push(self.voxels[i].position.0);
-            voxel_slice.push(self.voxels[i].position.1);
-            voxel_slice.push(self.voxels[i].position.2);
-            voxel_slice.push(self.voxels[i].colorindex);
-        }
-        buf_writer.write(voxel_slice.as_slice()).unwrap();
-    }
-
-    //start at size chunk
-    pub(crate) fn read(input: &Vec<u8>, cursor: &mut i32, id: i32) -> Model {
-        use crate::riff::i32_from_vec;
-        *cursor += 12;
-        let size_x = i32_from_vec(input, cursor) as u16;
-        *cursor += 4;
-        let size_y = i32_from_vec(input, cursor) as u16;
-        *cursor += 4;
-        let size_z = i32_from_vec(input, cursor) as u16;
-        *cursor += 16;
-
-        let num_of_voxels = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let mut voxels = Vec::new();
-        for i in 0..num_of_voxels {
-            let x = input[(*cursor + 4 * i) as usize];
-            let y = input[(*cursor + 1 + 4 * i) as usize];
-            let z = input[(*cursor + 2 + 4 * i) as usize];
-            let index = input[(*cursor + 3 + 4 * i) as usize];
-            voxels.push(Voxel::new(x, y, z, index))
+    fn write_voxels<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        let mut voxel_slice = Vec::with_capacity(self.voxels.len() * 4);
+        for voxel in self.voxels.iter() {
+            voxel_slice.push(voxel.position.0);
+            voxel_slice.push(voxel.position.1);
+            voxel_slice.push(voxel.position.2);
+            voxel_slice.push(voxel.colorindex);
         }
+        write_slice(buf_writer, voxel_slice.as_slice())
+    }
 
-        Model {
+    //reads a model from a SIZE chunk and the XYZI chunk after it
+    pub(crate) fn read(size: &Chunk, xyzi: &Chunk, id: i32) -> Result<Model, VoxError> {
+        use crate::riff::{read_bytes, read_i32};
+        let (size_x, size_y, size_z) = size.parse(|input, cursor| {
+            Ok((
+                read_i32(input, cursor)? as u16,
+                read_i32(input, cursor)? as u16,
+                read_i32(input, cursor)? as u16,
+            ))
+        })?;
+
+        let voxels = xyzi.parse(|input, cursor| {
+            let num_of_voxels = read_i32(input, cursor)?;
+            //voxels at the same position as one before them replace it
+            let mut voxels = Voxels::new(Storage::default(), (size_x, size_y, size_z));
+            for _i in 0..num_of_voxels {
+                let offset = *cursor;
+                let bytes = read_bytes(input, cursor, 4)?;
+                if bytes[3] == 0 {
+                    return Err(VoxError::InvalidColorIndex { offset });
+                }
+                voxels.insert(Voxel::new(bytes[0], bytes[1], bytes[2], bytes[3]));
+            }
+            Ok(voxels)
+        })?;
+
+        Ok(Model {
             size: (size_x, size_y, size_z),
             voxels,
             position: None,
             rotation: None,
             layer: None,
             name: None,
+            hidden: None,
+            place_mode: PlaceMode::default(),
+            frames: vec![],
+            model_frames: vec![],
             id,
-        }
+            group: None,
+            extras: NodeExtras::default(),
+        })
     }
 
     pub(crate) fn to_node(&self) -> Node {
-        let mut attributes = NodeAttributes::new();
-        attributes.name = self.name.clone();
-        let mut transform_node = Node::new(NodeType::Transform(self.transform_data()), attributes);
-        let shape_node = Node::new(NodeType::Shape(self.id), NodeAttributes::new());
-        transform_node.add_child(shape_node);
-
-        transform_node
-    }
-
-    //puts data into Transform struct
-    pub(crate) fn transform_data(&self) -> Transform {
-        Transform {
-            layer: self.layer.unwrap_or_else(|| 0),
-            rotation: match self.rotation {
-                None => None,
-                Some(rot) => Some(rot as i32),
-            },
-            translation: self.position,
-        }
+        self.extras.to_node(
+            self.name.clone(),
+            self.hidden,
+            self.transform_data(),
+            self.extras.shape(self.id, &self.model_frames),
+        )
     }
 
-    //size in bytes when written
+    //puts data into NodeTransform struct
+    pub(crate) fn transform_data(&self) -> NodeTransform {
+        self.extras
+            .transform(self.layer, self.rotation, self.position, &self.frames)
+    }
+
+    //size in bytes of the SIZE and XYZI chunks when written
     pub(crate) fn get_size(&self) -> i32 {
-        self.voxels.len() as i32 * 4 + 4
+        24 + 16 + self.voxels.len() as i32 * 4
     }
 
     //start of functions for users.
 
-    /// Adds a voxel to the model. It will return an error if the voxel does not fit inside the model.
+    /// Adds a voxel to the model. The place mode of the model is used if there already is a
+    /// voxel at the position. It will return an error if the voxel does not fit inside the model.
     ///
     /// # Example
     /// ```
@@ -154,14 +166,10 @@ impl Model {
     /// vox.models[0].add_voxel(voxel);
     /// ```
     pub fn add_voxel(&mut self, new_voxel: Voxel) -> Result<(), &str> {
-        if (new_voxel.position.0 + 1) as u16 > self.size.0
-            || (new_voxel.position.1 + 1) as u16 > self.size.1
-            || (new_voxel.position.2 + 1) as u16 > self.size.2
-        {
+        if !self.contains_pos(new_voxel.position) {
             return Err("Voxel position greater than Voxobject size");
         }
-        self.voxels.push(new_voxel);
-        Ok(())
+        self.place_voxel(new_voxel)
     }
 
     /// Makes the size of the model as small as possible
@@ -179,7 +187,8 @@ impl Model {
         self.voxels.clear();
     }
 
-    /// Sets the size of the model. Size must be less than or equal to 256 on all axis.
+    /// Sets the size of the model. Size must be less than or equal to 256 on all axis. Voxels
+    /// outside the new size are removed.
     ///
     /// # Example
     /// ```
@@ -194,6 +203,28 @@ impl Model {
             panic!("size can not be greater than 256");
         }
         self.size = (x, y, z);
+        let storage = self.voxels.storage();
+        self.voxels.rebuild(storage, self.size);
+    }
+
+    /// Returns how the model finds its voxels by position.
+    pub fn storage(&self) -> Storage {
+        self.voxels.storage()
+    }
+
+    /// Changes how the model finds its voxels by position. The voxels are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, Storage};
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    /// model.set_storage(Storage::Dense);
+    /// assert!(model.is_voxel_at_pos(1, 2, 3));
+    /// ```
+    pub fn set_storage(&mut self, storage: Storage) {
+        self.voxels.rebuild(storage, self.size);
     }
 
     /// Makes the size of the model as small as possible
@@ -223,27 +254,30 @@ impl Model {
             }
         }
         //move voxels
-        for voxel in self.voxels.iter_mut() {
-            voxel.position = (
-                voxel.position.0 - smallest_pos.0,
-                voxel.position.1 - smallest_pos.1,
-                voxel.position.2 - smallest_pos.2,
-            )
-        }
+        self.voxels.change(
+            |voxel| {
+                voxel.position = (
+                    voxel.position.0 - smallest_pos.0,
+                    voxel.position.1 - smallest_pos.1,
+                    voxel.position.2 - smallest_pos.2,
+                )
+            },
+            self.size,
+        );
 
         for voxel in self.voxels.iter() {
             if (voxel.position.0 as u16) > new_size.0 - 1 {
-                new_size.0 = (voxel.position.0 + 1) as u16
+                new_size.0 = voxel.position.0 as u16 + 1
             }
             if (voxel.position.1 as u16) > new_size.1 - 1 {
-                new_size.1 = (voxel.position.1 + 1) as u16
+                new_size.1 = voxel.position.1 as u16 + 1
             }
             if (voxel.position.2 as u16) > new_size.2 - 1 {
-                new_size.2 = (voxel.position.2 + 1) as u16
+                new_size.2 = voxel.position.2 as u16 + 1
             }
         }
 
-        self.size = new_size
+        self.set_size(new_size.0, new_size.1, new_size.2)
     }
 
     /// Fills in the area between 2 points with voxels
@@ -255,6 +289,7 @@ impl Model {
     /// let mut vox = VoxFile::new(10,10,10);
     /// vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 1);
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub fn add_cube(
         &mut self,
         startx: u8,
@@ -268,15 +303,114 @@ impl Model {
         if endx as u16 > self.size.0 || endy as u16 > self.size.1 || endz as u16 > self.size.2 {
             return Err("Cube too large");
         }
-        for currentx in startx..endx {
-            for currenty in starty..endy {
-                for currentz in startz..endz {
-                    self.add_voxel(Voxel::new(currentx, currenty, currentz, colorindex))
-                        .unwrap();
+        let voxels = (startx..endx).flat_map(move |x| {
+            (starty..endy)
+                .flat_map(move |y| (startz..endz).map(move |z| Voxel::new(x, y, z, colorindex)))
+        });
+
+        self.place_voxels(voxels)
+    }
+
+    /// Calls the closure for every position in the model and adds a voxel with the color index it
+    /// returns. Returning None or 0 leaves the position alone. With the `rayon` feature the model
+    /// is filled one z slab per thread.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(16, 16, 16);
+    /// //a checkerboard with two colors
+    /// model
+    ///     .fill_with(|x, y, z| Some(1 + (x + y + z) % 2))
+    ///     .unwrap();
+    /// assert_eq!(model.get(1, 0, 0), Some(2));
+    ///
+    /// //stairs
+    /// model.clear_voxels();
+    /// model.fill_with(|x, _y, z| if z <= x { Some(3) } else { None }).unwrap();
+    /// assert!(model.is_voxel_at_pos(15, 0, 15));
+    /// ```
+    pub fn fill_with<F>(&mut self, closure: F) -> Result<(), &str>
+    where
+        F: Fn(u8, u8, u8) -> Option<u8> + Sync,
+    {
+        let size = self.size;
+        let slab = |z: u16| {
+            let mut voxels = Vec::new();
+            for y in 0..size.1 {
+                for x in 0..size.0 {
+                    match closure(x as u8, y as u8, z as u8) {
+                        None | Some(0) => {}
+                        Some(colorindex) => {
+                            voxels.push(Voxel::new(x as u8, y as u8, z as u8, colorindex))
+                        }
+                    }
                 }
             }
+            voxels
+        };
+
+        #[cfg(feature = "rayon")]
+        let slabs: Vec<Vec<Voxel>> = {
+            use rayon::prelude::*;
+            (0..size.2).into_par_iter().map(slab).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let slabs: Vec<Vec<Voxel>> = (0..size.2).map(slab).collect();
+
+        self.place_voxels(slabs.iter().flatten().cloned())
+    }
+
+    //adds a voxel using the place mode. The position must be inside the model.
+    fn place_voxel(&mut self, voxel: Voxel) -> Result<(), &'static str> {
+        let existing = match self.voxels.get(voxel.position) {
+            Some(existing) => existing.colorindex,
+            None => {
+                self.voxels.insert(voxel);
+                return Ok(());
+            }
+        };
+
+        match &self.place_mode {
+            PlaceMode::Replace => {
+                self.voxels.insert(voxel);
+            }
+            PlaceMode::KeepExisting => {}
+            PlaceMode::Error => return Err("Voxel already at position"),
+            PlaceMode::Merge(closure) => match closure(existing, voxel.colorindex) {
+                0 => {
+                    self.voxels.remove(voxel.position);
+                }
+                colorindex => {
+                    self.voxels.insert(Voxel {
+                        colorindex,
+                        ..voxel
+                    });
+                }
+            },
         }
+        Ok(())
+    }
 
+    //adds voxels using the place mode. With PlaceMode::Error nothing is added if any voxel is
+    //already taken. The positions must be inside the model.
+    pub(crate) fn place_voxels<I>(&mut self, voxels: I) -> Result<(), &'static str>
+    where
+        I: Iterator<Item = Voxel> + Clone,
+    {
+        if let PlaceMode::Error = self.place_mode {
+            if voxels
+                .clone()
+                .any(|voxel| self.voxels.get(voxel.position).is_some())
+            {
+                return Err("Voxel already at position");
+            }
+        }
+
+        for voxel in voxels {
+            self.place_voxel(voxel)?;
+        }
         Ok(())
     }
 
@@ -291,25 +425,18 @@ impl Model {
     /// assert_eq!(true, vox.models[0].is_voxel_at_pos(3, 4, 3));
     /// ```
     pub fn is_voxel_at_pos(&self, x: u8, y: u8, z: u8) -> bool {
-        for voxel in self.voxels.iter() {
-            if voxel.position.0 == x && voxel.position.1 == y && voxel.position.2 == z {
-                return true;
-            }
-        }
-        false
+        self.voxels.get((x, y, z)).is_some()
     }
 
-    //needs testing
-    fn check_voxels_pos(&mut self) {
-        let size = self.size;
-        self.voxels.retain(|voxel| {
-            (voxel.position.0 as u16) < size.0
-                && (voxel.position.1 as u16) < size.1
-                && (voxel.position.2 as u16) < size.2
-        });
+    //checks if the position is inside the size of the model
+    fn contains_pos(&self, position: (u8, u8, u8)) -> bool {
+        (position.0 as u16) < self.size.0
+            && (position.1 as u16) < self.size.1
+            && (position.2 as u16) < self.size.2
     }
 
-    /// Adds a voxel at certain position
+    /// Adds a voxel at certain position. The place mode of the model is used if there already is
+    /// a voxel at the position.
     ///
     /// # Example
     /// ```
@@ -326,14 +453,10 @@ impl Model {
     /// assert_eq!(2, vox.models[0].num_of_voxels());
     /// ```
     pub fn add_voxel_at_pos(&mut self, x: u8, y: u8, z: u8, voxel_index: u8) -> Result<(), &str> {
-        if (x + 1) as u16 > self.size.0
-            || (y + 1) as u16 > self.size.1
-            || (z + 1) as u16 > self.size.2
-        {
+        if !self.contains_pos((x, y, z)) {
             return Err("Position greater than Voxobject size");
         }
-        self.voxels.push(Voxel::new(x, y, z, voxel_index));
-        Ok(())
+        self.place_voxel(Voxel::new(x, y, z, voxel_index))
     }
 
     /// Returns the number of voxels in the model
@@ -377,7 +500,9 @@ impl Model {
         self.voxels.retain(closure);
     }
 
-    /// Changes all the voxels in the Voxobject with the closure
+    /// Changes all the voxels in the Voxobject with the closure. If voxels are moved to the same
+    /// position the last one is kept and voxels moved outside the model are removed. Voxels
+    /// given a color index of 0 are also removed.
     ///
     /// # Example
     /// ```
@@ -396,14 +521,163 @@ impl Model {
     where
         T: FnMut(&mut Voxel),
     {
-        let voxel_iter = self.voxels.iter_mut();
+        self.voxels.change(|voxel| closure(voxel), self.size);
+    }
+
+    /// Returns the color index of the voxel at the position or None if there is no voxel there.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.set(1, 2, 3, 5).unwrap();
+    /// assert_eq!(model.get(1, 2, 3), Some(5));
+    /// assert_eq!(model.get(3, 2, 1), None);
+    /// ```
+    pub fn get(&self, x: u8, y: u8, z: u8) -> Option<u8> {
+        self.voxels.get((x, y, z)).map(|voxel| voxel.colorindex)
+    }
 
-        for voxel in voxel_iter {
-            closure(voxel);
+    /// Sets the color index of the voxel at the position. A color index of 0 removes the voxel.
+    /// The place mode is not used so a voxel already there is always replaced. It will return an
+    /// error if the position is not inside the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.set(1, 2, 3, 5).unwrap();
+    /// model.set(1, 2, 3, 0).unwrap();
+    /// assert_eq!(model.get(1, 2, 3), None);
+    /// assert!(model.set(10, 0, 0, 1).is_err());
+    /// ```
+    pub fn set(&mut self, x: u8, y: u8, z: u8, colorindex: u8) -> Result<(), &str> {
+        if !self.contains_pos((x, y, z)) {
+            return Err("Position greater than Voxobject size");
+        }
+        if colorindex == 0 {
+            self.voxels.remove((x, y, z));
+        } else {
+            self.voxels.insert(Voxel::new(x, y, z, colorindex));
         }
+        Ok(())
+    }
+
+    /// Removes the voxel at the position and returns its color index.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.set(1, 2, 3, 5).unwrap();
+    /// assert_eq!(model.remove(1, 2, 3), Some(5));
+    /// assert_eq!(model.remove(1, 2, 3), None);
+    /// ```
+    pub fn remove(&mut self, x: u8, y: u8, z: u8) -> Option<u8> {
+        self.voxels.remove((x, y, z)).map(|voxel| voxel.colorindex)
+    }
+
+    /// Returns all voxels in the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_cube(0, 0, 0, 2, 2, 2, 1).unwrap();
+    /// for voxel in model.voxels() {
+    ///     assert!(voxel.position.0 < 2);
+    /// }
+    /// ```
+    pub fn voxels(&self) -> impl Iterator<Item = &Voxel> {
+        self.voxels.iter()
     }
 
-    pub fn get_id(&self)-> i32{
+    /// Returns the voxels with their color indexes able to be changed. Voxels that have their
+    /// color index set to 0 are removed once it is dropped, like set() does.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_cube(0, 0, 0, 10, 10, 10, 1).unwrap();
+    /// //color the top of the model and remove the bottom
+    /// for (position, colorindex) in model.voxels_mut().iter_mut() {
+    ///     if position.2 == 9 {
+    ///         *colorindex = 2;
+    ///     } else if position.2 == 0 {
+    ///         *colorindex = 0;
+    ///     }
+    /// }
+    /// assert_eq!(model.get(0, 0, 9), Some(2));
+    /// assert_eq!(model.get(0, 0, 0), None);
+    /// assert_eq!(model.num_of_voxels(), 900);
+    /// ```
+    pub fn voxels_mut(&mut self) -> VoxelsMut<'_> {
+        VoxelsMut::new(&mut self.voxels)
+    }
+
+    /// Returns the voxels in the box between min and max. Both min and max are inside the box.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_cube(0, 0, 0, 10, 10, 10, 1).unwrap();
+    /// assert_eq!(model.iter_in_region((2, 2, 2), (3, 3, 3)).count(), 8);
+    /// ```
+    pub fn iter_in_region(
+        &self,
+        min: (u8, u8, u8),
+        max: (u8, u8, u8),
+    ) -> impl Iterator<Item = &Voxel> {
+        self.voxels.iter().filter(move |voxel| {
+            let (x, y, z) = voxel.position;
+            (min.0..=max.0).contains(&x)
+                && (min.1..=max.1).contains(&y)
+                && (min.2..=max.2).contains(&z)
+        })
+    }
+
+    pub fn get_id(&self) -> i32 {
         self.id
     }
+
+    /// Returns the id of the group the model is in. None is the root of the scene.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let group = vox.add_group(None, Transform::default());
+    /// vox.set_model_group(0, Some(group)).unwrap();
+    /// assert_eq!(vox.models[0].group(), Some(group));
+    /// ```
+    pub fn group(&self) -> Option<i32> {
+        self.group
+    }
+}
+
+impl IntoIterator for Model {
+    type Item = Voxel;
+    type IntoIter = std::vec::IntoIter<Voxel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.voxels.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Model {
+    type Item = &'a Voxel;
+    type IntoIter = std::slice::Iter<'a, Voxel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.voxels.iter()
+    }
 }
@@ -1,14 +1,14 @@
-use crate::riff::{nGRP, nSHP, nTRN, Dict, VoxString};
-use crate::VoxFile;
-use std::fs::File;
-use std::io::BufWriter;
+use crate::riff::{nGRP, nSHP, nTRN, Dict};
+use crate::rotation::Rotation;
+use crate::transform::Keyframe;
+use std::io;
+use std::io::Write;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum NodeType {
-    Transform(Transform),
+    Transform(NodeTransform),
     Group,
-    //shape with id of model.
-    Shape(i32),
+    Shape(Shape),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -62,20 +62,18 @@ impl Node {
 
         num
     }
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        match &(*self).node_type {
-            NodeType::Transform(trans) => {
-                nTRN {
-                    node_id: self.id,
-                    node_attributes: self.attributes.to_dict(),
-                    child_node_id: self.children_ids[0],
-                    reserved_id: -1,
-                    layer_id: trans.layer,
-                    num_of_frames: 1,
-                    frame_attributes: trans.to_dict(),
-                }
-                .write(buf_writer);
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        match &self.node_type {
+            NodeType::Transform(trans) => nTRN {
+                node_id: self.id,
+                node_attributes: self.attributes.to_dict(),
+                child_node_id: self.children_ids[0],
+                reserved_id: -1,
+                layer_id: trans.layer,
+                num_of_frames: trans.frames.len() as i32,
+                frame_attributes: trans.to_dicts(),
             }
+            .write(buf_writer),
 
             NodeType::Group => nGRP {
                 node_id: self.id,
@@ -85,44 +83,42 @@ impl Node {
             }
             .write(buf_writer),
 
-            NodeType::Shape(model_id) => nSHP {
+            NodeType::Shape(shape) => nSHP {
                 node_id: self.id,
                 node_attributes: self.attributes.to_dict(),
-                num_of_models: 1,
-                model_id: *model_id,
-                model_attributes: Dict {
-                    num_of_pairs: 0,
-                    pairs: vec![],
-                },
+                num_of_models: shape.models.len() as i32,
+                models: shape.to_models(),
             }
             .write(buf_writer),
         }
     }
 
-    pub fn write_children(&self, buf_writer: &mut BufWriter<File>) {
+    pub fn write_children<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
         for child in self.children.iter() {
-            child.write(buf_writer);
-            child.write_children(buf_writer);
+            child.write(buf_writer)?;
+            child.write_children(buf_writer)?;
         }
+        Ok(())
     }
 
-    pub fn write_all(&mut self, buf_writer: &mut BufWriter<File>) {
+    pub fn write_all<W: Write>(&mut self, buf_writer: &mut W) -> io::Result<()> {
         self.number_nodes(0);
         self.number_children_ids();
-        self.write(buf_writer);
+        self.write(buf_writer)?;
         self.write_children(buf_writer)
     }
 
+    //size in bytes when written including the chunk header
     pub fn get_size(&self) -> i32 {
-        match &(*self).node_type {
+        12 + match &self.node_type {
             NodeType::Transform(trans) => nTRN {
                 node_id: 0,
                 node_attributes: self.attributes.to_dict(),
                 child_node_id: 0,
                 reserved_id: 0,
                 layer_id: -1,
-                num_of_frames: 1,
-                frame_attributes: trans.to_dict(),
+                num_of_frames: trans.frames.len() as i32,
+                frame_attributes: trans.to_dicts(),
             }
             .get_size(),
 
@@ -134,15 +130,11 @@ impl Node {
             }
             .get_size(),
 
-            NodeType::Shape(model_id) => nSHP {
+            NodeType::Shape(shape) => nSHP {
                 node_id: 0,
                 node_attributes: self.attributes.to_dict(),
-                num_of_models: 1,
-                model_id: *model_id,
-                model_attributes: Dict {
-                    num_of_pairs: 0,
-                    pairs: vec![],
-                },
+                num_of_models: shape.models.len() as i32,
+                models: shape.to_models(),
             }
             .get_size(),
         }
@@ -162,51 +154,18 @@ impl Node {
     }
 
     pub fn has_child_shape(&self) -> bool {
-        for child in self.children.iter() {
-            match child.node_type {
-                NodeType::Shape(_) => return true,
-                _ => {}
-            }
-        }
-        false
-    }
-
-    pub fn get_child_data_to_models(&self, voxfile: &mut VoxFile, used_ids: &mut Vec<i32>) {
-        self.make_model_data(voxfile, used_ids);
-        for child in self.children.iter() {
-            child.get_child_data_to_models(voxfile, used_ids);
-        }
-    }
-
-    pub fn make_model_data(&self, voxfile: &mut VoxFile, used_ids: &mut Vec<i32>) {
-        let data = VoxFile::check_transform(self);
-        if data.is_some() {
-            if used_ids.contains(&data.as_ref().unwrap().0) {
-                voxfile.add_copy(
-                    data.as_ref().unwrap().0,
-                    data.as_ref().unwrap().1,
-                    data.as_ref().unwrap().2,
-                    data.as_ref().unwrap().3,
-                    data.as_ref().unwrap().4.clone(),
-                );
-            } else {
-                voxfile.change_model_data(
-                    data.as_ref().unwrap().0,
-                    data.as_ref().unwrap().1,
-                    data.as_ref().unwrap().2,
-                    data.as_ref().unwrap().3,
-                    data.as_ref().unwrap().4.clone(),
-                );
-                used_ids.push(data.as_ref().unwrap().0);
-            }
-        }
+        self.children
+            .iter()
+            .any(|child| matches!(child.node_type, NodeType::Shape(_)))
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct NodeAttributes {
     pub name: Option<String>,
     pub hidden: Option<bool>,
+    //pairs that are not used by this crate. They are kept so they can be written back unchanged
+    pub other_attributes: Vec<(String, String)>,
 }
 
 impl NodeAttributes {
@@ -214,115 +173,289 @@ impl NodeAttributes {
         NodeAttributes {
             name: None,
             hidden: None,
+            other_attributes: vec![],
         }
     }
 
     pub fn to_dict(&self) -> Dict {
-        Dict {
-            num_of_pairs: self.num_of_pairs(),
-            pairs: self.to_pairs(),
-        }
+        Dict::new(self.to_pairs())
     }
 
-    pub fn num_of_pairs(&self) -> i32 {
-        if self.name.is_some() && self.hidden.is_some() {
-            2
-        } else if self.name.is_some() || self.hidden.is_some() {
-            1
-        } else {
-            0
+    pub fn to_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+
+        if let Some(name) = self.name.clone() {
+            pairs.push((String::from("_name"), name));
+        }
+
+        if let Some(value) = self.hidden {
+            pairs.push((String::from("_hidden"), bool_to_string(value)));
         }
+
+        pairs.extend(self.other_attributes.iter().cloned());
+        pairs
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Shape {
+    pub models: Vec<ShapeModel>,
+}
+
+impl Shape {
+    pub fn to_models(&self) -> Vec<(i32, Dict)> {
+        self.models
+            .iter()
+            .map(|model| {
+                let mut pairs = Vec::new();
+                if let Some(index) = model.index {
+                    pairs.push((String::from("_f"), index.to_string()));
+                }
+                pairs.extend(model.other_attributes.iter().cloned());
+                (model.model_id, Dict::new(pairs))
+            })
+            .collect()
     }
+}
+
+//a model shown by a shape node from the frame index on
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShapeModel {
+    pub model_id: i32,
+    pub index: Option<u32>,
+    pub other_attributes: Vec<(String, String)>,
+}
 
-    pub fn to_pairs(&self) -> Vec<(VoxString, VoxString)> {
-        let mut pairs: Vec<(VoxString, VoxString)> = Vec::new();
+//unused attributes of a frame or a model of a shape with its frame index
+pub type FrameAttributes = (Option<u32>, Vec<(String, String)>);
+
+//parts of the nodes of a model that this crate does not change. They are kept so loading and
+//saving a file does not lose them.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NodeExtras {
+    //where the node was in its group when it was loaded. Nodes without one are written last.
+    pub order: Option<usize>,
+    pub transform_attributes: Vec<(String, String)>,
+    //frame index of the first frame and first model. Other frames always have one.
+    pub first_frame: Option<u32>,
+    pub first_model_frame: Option<u32>,
+    //the transform node had no frames. It is written without one until something is set.
+    pub no_frames: bool,
+    //unused attributes of each frame and each model of the shape by their frame index
+    pub frame_attributes: Vec<FrameAttributes>,
+    pub model_attributes: Vec<FrameAttributes>,
+    //attributes of the shape or group node below the transform node
+    pub child_attributes: NodeAttributes,
+}
 
-        match self.name.clone() {
-            Some(name) => pairs.push((
-                VoxString::new(5, String::from("_name")),
-                VoxString::new(name.len() as i32, name),
-            )),
-            None => {}
+impl NodeExtras {
+    pub fn from_nodes(
+        transform_node: &Node,
+        transform: &NodeTransform,
+        child_node: &Node,
+        order: usize,
+    ) -> NodeExtras {
+        let (first_model_frame, model_attributes) = match &child_node.node_type {
+            NodeType::Shape(shape) => (
+                shape.models.first().and_then(|model| model.index),
+                shape
+                    .models
+                    .iter()
+                    .map(|model| (model.index, model.other_attributes.clone()))
+                    .collect(),
+            ),
+            _ => (None, vec![]),
         };
 
-        match self.hidden {
-            Some(value) => pairs.push((
-                VoxString::new(7, String::from("_hidden")),
-                VoxString::new(1, bool_to_string(value)),
-            )),
-            None => {}
+        NodeExtras {
+            order: Some(order),
+            transform_attributes: transform_node.attributes.other_attributes.clone(),
+            first_frame: transform.frames.first().and_then(|frame| frame.index),
+            first_model_frame,
+            no_frames: transform.frames.is_empty(),
+            frame_attributes: transform
+                .frames
+                .iter()
+                .map(|frame| (frame.index, frame.other_attributes.clone()))
+                .collect(),
+            model_attributes,
+            child_attributes: child_node.attributes.clone(),
+        }
+    }
+
+    //the first frame uses the translation and rotation given and the rest are keyframes
+    pub fn transform(
+        &self,
+        layer: Option<i32>,
+        rotation: Option<Rotation>,
+        translation: Option<(i32, i32, i32)>,
+        keyframes: &[(u32, Keyframe)],
+    ) -> NodeTransform {
+        let layer = layer.unwrap_or(0);
+        if self.no_frames && rotation.is_none() && translation.is_none() && keyframes.is_empty() {
+            return NodeTransform {
+                layer,
+                frames: vec![],
+            };
+        }
+
+        let mut frames = vec![NodeFrame {
+            index: self.first_frame,
+            rotation,
+            translation,
+            other_attributes: vec![],
+        }];
+        for (index, keyframe) in keyframes.iter() {
+            frames.push(NodeFrame {
+                index: Some(*index),
+                rotation: keyframe.rotation,
+                translation: keyframe.translation,
+                other_attributes: vec![],
+            });
+        }
+        let mut attributes = self.frame_attributes.clone();
+        for frame in frames.iter_mut() {
+            frame.other_attributes = take_attributes(&mut attributes, frame.index);
+        }
+
+        NodeTransform { layer, frames }
+    }
+
+    pub fn shape(&self, model_id: i32, model_frames: &[(u32, i32)]) -> NodeType {
+        let mut models = vec![ShapeModel {
+            model_id,
+            index: self.first_model_frame,
+            other_attributes: vec![],
+        }];
+        for (index, model_id) in model_frames.iter() {
+            models.push(ShapeModel {
+                model_id: *model_id,
+                index: Some(*index),
+                other_attributes: vec![],
+            });
+        }
+        let mut attributes = self.model_attributes.clone();
+        for model in models.iter_mut() {
+            model.other_attributes = take_attributes(&mut attributes, model.index);
+        }
+
+        NodeType::Shape(Shape { models })
+    }
+
+    //makes a transform node with a shape or group node as its child
+    pub fn to_node(
+        &self,
+        name: Option<String>,
+        hidden: Option<bool>,
+        transform: NodeTransform,
+        child_type: NodeType,
+    ) -> Node {
+        let attributes = NodeAttributes {
+            name,
+            hidden,
+            other_attributes: self.transform_attributes.clone(),
         };
 
-        pairs
+        let mut transform_node = Node::new(NodeType::Transform(transform), attributes);
+        transform_node.add_child(Node::new(child_type, self.child_attributes.clone()));
+
+        transform_node
     }
 }
 
+//a node from a loaded file that is not a model, copy or group, such as a shape node without any
+//models. It is written back unchanged.
 #[derive(Debug, PartialEq, Clone)]
-pub struct Transform {
+pub struct RawNode {
+    pub group: Option<i32>,
+    pub order: Option<usize>,
+    pub node: Node,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NodeTransform {
     pub layer: i32,
-    //need to make rotation type
-    pub rotation: Option<i32>,
-    pub translation: Option<(i32, i32, i32)>,
+    pub frames: Vec<NodeFrame>,
 }
 
-impl Transform {
-    pub fn to_dict(&self) -> Dict {
-        Dict {
-            num_of_pairs: self.num_of_pairs(),
-            pairs: self.get_pairs(),
-        }
+impl NodeTransform {
+    pub fn to_dicts(&self) -> Vec<Dict> {
+        self.frames.iter().map(|frame| frame.to_dict()).collect()
+    }
+
+    //a transform node read from a file may have no frames
+    pub fn first_frame(&self) -> NodeFrame {
+        self.frames.first().cloned().unwrap_or_default()
+    }
+
+    //keyframes are all frames after the first one
+    pub fn keyframes(&self) -> Vec<(u32, Keyframe)> {
+        self.frames
+            .iter()
+            .skip(1)
+            .map(|frame| {
+                let keyframe = Keyframe {
+                    translation: frame.translation,
+                    rotation: frame.rotation,
+                };
+                (frame.index.unwrap_or(0), keyframe)
+            })
+            .collect()
     }
 
-    pub fn num_of_pairs(&self) -> i32 {
-        if self.rotation.is_some() && self.translation.is_some() {
-            2
-        } else if self.rotation.is_some() || self.translation.is_some() {
-            1
-        } else {
-            0
+    pub fn default() -> NodeTransform {
+        NodeTransform {
+            layer: 0,
+            frames: vec![NodeFrame::default()],
         }
     }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NodeFrame {
+    pub index: Option<u32>,
+    pub rotation: Option<Rotation>,
+    pub translation: Option<(i32, i32, i32)>,
+    //frame attributes that are not used by this crate
+    pub other_attributes: Vec<(String, String)>,
+}
+
+impl NodeFrame {
+    pub fn to_dict(&self) -> Dict {
+        Dict::new(self.get_pairs())
+    }
 
-    pub fn get_pairs(&self) -> Vec<(VoxString, VoxString)> {
+    pub fn get_pairs(&self) -> Vec<(String, String)> {
         let mut pairs = Vec::new();
-        match self.rotation {
-            Some(rot) => pairs.push((
-                VoxString::new(2, String::from("_r")),
-                //it was here
-                VoxString::new(rot.to_string().len() as i32, rot.to_string()),
-            )),
-            None => {}
+        if let Some(rot) = self.rotation {
+            pairs.push((String::from("_r"), rot.to_byte().to_string()));
         }
 
-        match self.translation {
-            Some(_) => pairs.push((
-                VoxString::new(2, String::from("_t")),
-                VoxString::new(
-                    self.translation_to_string().len() as i32,
-                    self.translation_to_string(),
-                ),
-            )),
-            None => {}
+        if let Some((x, y, z)) = self.translation {
+            pairs.push((String::from("_t"), format!("{} {} {}", x, y, z)));
         }
 
-        pairs
-    }
+        if let Some(index) = self.index {
+            pairs.push((String::from("_f"), index.to_string()));
+        }
 
-    pub fn translation_to_string(&self) -> String {
-        format!(
-            "{} {} {}",
-            self.translation.unwrap().0,
-            self.translation.unwrap().1,
-            self.translation.unwrap().2
-        )
+        pairs.extend(self.other_attributes.iter().cloned());
+        pairs
     }
+}
 
-    pub fn default() -> Transform {
-        Transform {
-            layer: 0,
-            rotation: None,
-            translation: None,
-        }
+//takes the unused attributes of the frame with the index given. Attributes are only kept while
+//there still is a frame with that index.
+fn take_attributes(
+    attributes: &mut Vec<FrameAttributes>,
+    index: Option<u32>,
+) -> Vec<(String, String)> {
+    match attributes
+        .iter()
+        .position(|(attributes_index, _)| *attributes_index == index)
+    {
+        Some(position) => attributes.remove(position).1,
+        None => vec![],
     }
 }
 
@@ -0,0 +1,212 @@
+use crate::axis::{Axis, Overflow};
+use crate::model::Model;
+use crate::rotation::Rotation;
+use crate::storage::Voxels;
+use crate::voxel::Voxel;
+
+//these change the voxel data. The model stays in the same place in the scene.
+impl Model {
+    /// Turns the voxels 90 degrees around the axis a number of times. Negative turns go the other
+    /// way. The size is changed to fit the turned voxels.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, Model};
+    ///
+    /// let mut model = Model::new(10, 4, 2);
+    /// model.add_voxel_at_pos(9, 0, 0, 1).unwrap();
+    /// model.rotate_90(Axis::Z, 1);
+    /// assert_eq!(model.size, (4, 10, 2));
+    /// assert!(model.is_voxel_at_pos(3, 9, 0));
+    /// ```
+    pub fn rotate_90(&mut self, axis: Axis, turns: i32) {
+        let rotation = match axis {
+            Axis::X => Rotation::from_euler_90s(turns, 0, 0),
+            Axis::Y => Rotation::from_euler_90s(0, turns, 0),
+            Axis::Z => Rotation::from_euler_90s(0, 0, turns),
+        };
+        self.apply_rotation(rotation);
+    }
+
+    /// Flips the voxels along the axis.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, Model};
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    /// model.mirror(Axis::X);
+    /// assert!(model.is_voxel_at_pos(8, 2, 3));
+    /// ```
+    pub fn mirror(&mut self, axis: Axis) {
+        let matrix = match axis {
+            Axis::X => [[-1, 0, 0], [0, 1, 0], [0, 0, 1]],
+            Axis::Y => [[1, 0, 0], [0, -1, 0], [0, 0, 1]],
+            Axis::Z => [[1, 0, 0], [0, 1, 0], [0, 0, -1]],
+        };
+        self.apply_rotation(Rotation::from_matrix(matrix).unwrap());
+    }
+
+    /// Rotates the voxels so they look the way the rotation would show them. The voxels are
+    /// moved back inside the model and the size is changed to fit. To bake the rotation of the
+    /// model into the voxels take it out of the model first.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, Rotation};
+    ///
+    /// let mut model = Model::new(3, 4, 5);
+    /// model.add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    /// model.rotation = Some(Rotation::rotate_x90());
+    ///
+    /// let rotation = model.rotation.take().unwrap();
+    /// model.apply_rotation(rotation);
+    /// assert_eq!(model.size, (3, 5, 4));
+    /// //y goes to z, so z goes to -y
+    /// assert!(model.is_voxel_at_pos(0, 4, 0));
+    /// ```
+    pub fn apply_rotation(&mut self, rotation: Rotation) {
+        let matrix = rotation.to_matrix();
+        let old_size = [self.size.0, self.size.1, self.size.2];
+        let mut size = [0; 3];
+        let mut shift = [0; 3];
+        for (row, values) in matrix.iter().enumerate() {
+            let column = values.iter().position(|value| *value != 0).unwrap();
+            size[row] = old_size[column];
+            //flipped axes start at the far side of the model
+            if values[column] < 0 {
+                shift[row] = old_size[column] as i32 - 1;
+            }
+        }
+        let size = (size[0], size[1], size[2]);
+
+        self.voxels.change(
+            |voxel| {
+                let (x, y, z) = rotation.apply((
+                    voxel.position.0 as i32,
+                    voxel.position.1 as i32,
+                    voxel.position.2 as i32,
+                ));
+                voxel.position = (
+                    (x + shift[0]) as u8,
+                    (y + shift[1]) as u8,
+                    (z + shift[2]) as u8,
+                );
+            },
+            size,
+        );
+        self.size = size;
+    }
+
+    /// Moves the voxels inside the model. The overflow picks what happens to voxels moved past
+    /// the edge. With Overflow::Grow the model gets larger, and its position is changed so the
+    /// model itself stays in the same place in the scene and only the voxels move. It will
+    /// return an error if the overflow is Overflow::Grow and the model would be larger than 256.
+    /// Nothing is changed if there is an error.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, Overflow};
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_voxel_at_pos(8, 0, 0, 1).unwrap();
+    ///
+    /// model.translate(3, 0, 0, Overflow::Wrap).unwrap();
+    /// assert!(model.is_voxel_at_pos(1, 0, 0));
+    ///
+    /// model.translate(0, 0, 15, Overflow::Grow).unwrap();
+    /// assert_eq!(model.size, (10, 10, 16));
+    /// //voxels moved below 0 move the rest of the model up
+    /// model.translate(-2, 0, 0, Overflow::Grow).unwrap();
+    /// assert_eq!(model.size, (11, 10, 16));
+    /// assert!(model.is_voxel_at_pos(0, 0, 15));
+    /// assert!(model.translate(0, 300, 0, Overflow::Grow).is_err());
+    ///
+    /// model.translate(-2, 0, 0, Overflow::Clip).unwrap();
+    /// assert_eq!(model.num_of_voxels(), 0);
+    /// ```
+    pub fn translate(&mut self, x: i32, y: i32, z: i32, overflow: Overflow) -> Result<(), &str> {
+        //i64 so large offsets do not overflow
+        let offset = [x as i64, y as i64, z as i64];
+        let size = [self.size.0 as i64, self.size.1 as i64, self.size.2 as i64];
+        let moved: Vec<([i64; 3], u8)> = self
+            .voxels
+            .iter()
+            .map(|voxel| {
+                let position = [
+                    voxel.position.0 as i64 + offset[0],
+                    voxel.position.1 as i64 + offset[1],
+                    voxel.position.2 as i64 + offset[2],
+                ];
+                (position, voxel.colorindex)
+            })
+            .collect();
+
+        let voxels: Vec<Voxel> = match overflow {
+            Overflow::Clip => moved
+                .into_iter()
+                .filter(|(position, _)| (0..3).all(|i| position[i] >= 0 && position[i] < size[i]))
+                .map(|(position, colorindex)| voxel_at(position, colorindex))
+                .collect(),
+            Overflow::Wrap => moved
+                .into_iter()
+                .map(|(mut position, colorindex)| {
+                    for (value, size) in position.iter_mut().zip(size.iter()) {
+                        *value = value.rem_euclid(*size);
+                    }
+                    voxel_at(position, colorindex)
+                })
+                .collect(),
+            Overflow::Grow => {
+                //voxels below 0 move everything up by the shift
+                let mut low = [0; 3];
+                let mut high = size;
+                for (position, _) in moved.iter() {
+                    for i in 0..3 {
+                        low[i] = low[i].min(position[i]);
+                        high[i] = high[i].max(position[i] + 1);
+                    }
+                }
+                if (0..3).any(|i| high[i] - low[i] > 256) {
+                    return Err("Model would be larger than 256");
+                }
+
+                let new_size = (
+                    (high[0] - low[0]) as u16,
+                    (high[1] - low[1]) as u16,
+                    (high[2] - low[2]) as u16,
+                );
+                self.keep_in_scene(new_size, (-low[0] as i32, -low[1] as i32, -low[2] as i32));
+                self.size = new_size;
+                moved
+                    .into_iter()
+                    .map(|(position, colorindex)| {
+                        let shifted = [
+                            position[0] - low[0],
+                            position[1] - low[1],
+                            position[2] - low[2],
+                        ];
+                        voxel_at(shifted, colorindex)
+                    })
+                    .collect()
+            }
+        };
+
+        self.voxels = Voxels::new(self.voxels.storage(), self.size);
+        for voxel in voxels {
+            self.voxels.insert(voxel);
+        }
+        Ok(())
+    }
+}
+
+//a voxel at a position that is inside the model
+fn voxel_at(position: [i64; 3], colorindex: u8) -> Voxel {
+    Voxel::new(
+        position[0] as u8,
+        position[1] as u8,
+        position[2] as u8,
+        colorindex,
+    )
+}
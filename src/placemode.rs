@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+/// What happens when a voxel is added where there already is one.
+///
+/// # Example
+/// ```
+/// use create_vox::{Model, PlaceMode};
+///
+/// let mut model = Model::new(10, 10, 10);
+/// model.add_cube(0, 0, 0, 5, 5, 5, 1).unwrap();
+///
+/// model.place_mode = PlaceMode::KeepExisting;
+/// model.add_cube(0, 0, 0, 10, 10, 10, 2).unwrap();
+/// assert_eq!(model.get(0, 0, 0), Some(1));
+/// assert_eq!(model.get(9, 9, 9), Some(2));
+///
+/// //keep the larger color index
+/// model.place_mode = PlaceMode::merge(|existing, new| existing.max(new));
+/// model.add_voxel_at_pos(0, 0, 0, 3).unwrap();
+/// assert_eq!(model.get(0, 0, 0), Some(3));
+/// ```
+#[derive(Clone, Default)]
+pub enum PlaceMode {
+    /// The new voxel replaces the one already there.
+    #[default]
+    Replace,
+    /// The voxel already there is kept.
+    KeepExisting,
+    /// Adding the voxel returns an error. Nothing is added by a cube that hits a voxel.
+    Error,
+    /// The closure gets the color index already there and the new one and returns the color
+    /// index to use. Returning 0 removes the voxel.
+    Merge(Arc<dyn Fn(u8, u8) -> u8 + Send + Sync>),
+}
+
+impl PlaceMode {
+    /// Makes a merge mode from a closure.
+    pub fn merge<T>(closure: T) -> PlaceMode
+    where
+        T: Fn(u8, u8) -> u8 + Send + Sync + 'static,
+    {
+        PlaceMode::Merge(Arc::new(closure))
+    }
+}
@@ -0,0 +1,329 @@
+use crate::riff::{parse_floats, rCAM, rOBJ, Dict, ReadOrder};
+use std::io;
+use std::io::Write;
+
+/// How a camera projects the scene. Written as the `_mode` property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraMode {
+    Perspective,
+    Free,
+    Panorama,
+    Orthographic,
+    Isometric,
+    /// A mode not known by this crate. It is written back unchanged.
+    Other(String),
+}
+
+impl CameraMode {
+    fn to_str(&self) -> &str {
+        match self {
+            CameraMode::Perspective => "pers",
+            CameraMode::Free => "free",
+            CameraMode::Panorama => "pano",
+            CameraMode::Orthographic => "orth",
+            CameraMode::Isometric => "iso",
+            CameraMode::Other(string) => string,
+        }
+    }
+
+    fn from_str(string: &str) -> CameraMode {
+        match string {
+            "pers" => CameraMode::Perspective,
+            "free" => CameraMode::Free,
+            "pano" => CameraMode::Panorama,
+            "orth" => CameraMode::Orthographic,
+            "iso" => CameraMode::Isometric,
+            _ => CameraMode::Other(String::from(string)),
+        }
+    }
+}
+
+/// A camera setup stored in a rCAM chunk. Properties that are None are not written.
+#[derive(Debug, Clone)]
+pub struct Camera {
+    pub id: i32,
+    /// `_mode`
+    pub mode: Option<CameraMode>,
+    /// `_focus` the point the camera looks at
+    pub focus: Option<(f32, f32, f32)>,
+    /// `_angle` in degrees
+    pub angle: Option<(f32, f32, f32)>,
+    /// `_radius` distance from the focus
+    pub radius: Option<f32>,
+    /// `_frustum`
+    pub frustum: Option<f32>,
+    /// `_fov` field of view in degrees
+    pub fov: Option<f32>,
+    //properties that are not used by this crate
+    pub(crate) other_properties: Vec<(String, String)>,
+    pub(crate) read_order: ReadOrder,
+}
+
+impl Camera {
+    /// Creates a camera with the id given and no properties set.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Camera, CameraMode, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let mut camera = Camera::new(0);
+    /// camera.mode = Some(CameraMode::Perspective);
+    /// camera.focus = Some((0.0, 0.0, 5.0));
+    /// camera.angle = Some((30.0, 45.0, 0.0));
+    /// camera.radius = Some(40.0);
+    /// camera.fov = Some(45.0);
+    /// vox.cameras.push(camera);
+    /// ```
+    pub fn new(id: i32) -> Camera {
+        Camera {
+            id,
+            mode: None,
+            focus: None,
+            angle: None,
+            radius: None,
+            frustum: None,
+            fov: None,
+            other_properties: vec![],
+            read_order: ReadOrder::default(),
+        }
+    }
+
+    //values that can not be parsed are kept with the other properties so they are written back
+    //unchanged
+    pub(crate) fn from_chunk(chunk: &rCAM) -> Camera {
+        let mut camera = Camera::new(chunk.camera_id);
+        camera.read_order = ReadOrder::new(&chunk.attributes);
+        for (key, value) in chunk.attributes.pairs.iter() {
+            let value = value.content.as_str();
+            let parsed = match key.content.as_str() {
+                "_mode" => set_parsed(&mut camera.mode, Some(CameraMode::from_str(value))),
+                "_focus" => set_parsed(&mut camera.focus, parse_vector(value)),
+                "_angle" => set_parsed(&mut camera.angle, parse_vector(value)),
+                "_radius" => set_parsed(&mut camera.radius, value.parse().ok()),
+                "_frustum" => set_parsed(&mut camera.frustum, value.parse().ok()),
+                "_fov" => set_parsed(&mut camera.fov, value.parse().ok()),
+                _ => false,
+            };
+            if !parsed {
+                camera
+                    .other_properties
+                    .push((key.content.clone(), String::from(value)));
+            }
+        }
+
+        camera
+    }
+
+    pub(crate) fn to_chunk(&self) -> rCAM {
+        let mut pairs = Vec::new();
+        if let Some(mode) = &self.mode {
+            pairs.push((String::from("_mode"), String::from(mode.to_str())));
+        }
+        if let Some(focus) = self.focus {
+            pairs.push((String::from("_focus"), vector_to_string(focus)));
+        }
+        if let Some(angle) = self.angle {
+            pairs.push((String::from("_angle"), vector_to_string(angle)));
+        }
+        let floats = [
+            ("_radius", self.radius),
+            ("_frustum", self.frustum),
+            ("_fov", self.fov),
+        ];
+        for (key, value) in floats.iter() {
+            if let Some(value) = value {
+                pairs.push((String::from(*key), value.to_string()));
+            }
+        }
+        //values that could not be parsed are replaced by ones that have been set since
+        let other_properties = self
+            .other_properties
+            .iter()
+            .filter(|(key, _)| pairs.iter().all(|(set_key, _)| set_key != key))
+            .cloned()
+            .collect::<Vec<(String, String)>>();
+        pairs.extend(other_properties);
+
+        rCAM {
+            camera_id: self.id,
+            attributes: Dict::new(self.read_order.apply(pairs)),
+        }
+    }
+
+    pub(crate) fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        self.to_chunk().write(buf_writer)
+    }
+
+    //size in bytes when written including the chunk header
+    pub(crate) fn get_size(&self) -> i32 {
+        12 + self.to_chunk().get_size()
+    }
+}
+
+//the order properties were read in is not compared
+impl PartialEq for Camera {
+    fn eq(&self, other: &Camera) -> bool {
+        self.id == other.id
+            && self.mode == other.mode
+            && self.focus == other.focus
+            && self.angle == other.angle
+            && self.radius == other.radius
+            && self.frustum == other.frustum
+            && self.fov == other.fov
+            && self.other_properties == other.other_properties
+    }
+}
+
+/// Render settings stored in a rOBJ chunk, such as lighting, fog, bloom or the ground. The type
+/// is the `_type` property, for example `_bloom` or `_fog_uni`, and every other property is kept
+/// in the order it was read.
+#[derive(Debug, Clone)]
+pub struct RenderObject {
+    pub object_type: String,
+    /// (key, value) pairs other than `_type`
+    pub properties: Vec<(String, String)>,
+    //used to write `_type` back where it was read
+    pub(crate) read_order: ReadOrder,
+}
+
+impl RenderObject {
+    /// Creates a render object of the type given with no properties.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{RenderObject, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let mut bloom = RenderObject::new("_bloom");
+    /// bloom.set_property("_mix", 0.5);
+    /// vox.render_objects.push(bloom);
+    /// ```
+    pub fn new(object_type: &str) -> RenderObject {
+        RenderObject {
+            object_type: String::from(object_type),
+            properties: vec![],
+            read_order: ReadOrder::default(),
+        }
+    }
+
+    /// Gets the value of a property. Returns None if the property is not set.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::RenderObject;
+    ///
+    /// let mut fog = RenderObject::new("_fog_uni");
+    /// fog.set_property("_d", 0.1);
+    /// assert_eq!(fog.property("_d"), Some("0.1"));
+    /// assert_eq!(fog.property("_k"), None);
+    /// ```
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(property, _)| property == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of a property. Properties that are already set keep their place.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::RenderObject;
+    ///
+    /// let mut ground = RenderObject::new("_ground");
+    /// ground.set_property("_color", "255 255 255");
+    /// ground.set_property("_color", "0 0 0");
+    /// assert_eq!(ground.properties.len(), 1);
+    /// ```
+    pub fn set_property<T: ToString>(&mut self, key: &str, value: T) {
+        let value = value.to_string();
+        match self
+            .properties
+            .iter_mut()
+            .find(|(property, _)| property == key)
+        {
+            Some(pair) => pair.1 = value,
+            None => self.properties.push((String::from(key), value)),
+        }
+    }
+
+    /// Removes a property and returns its value.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::RenderObject;
+    ///
+    /// let mut bloom = RenderObject::new("_bloom");
+    /// bloom.set_property("_mix", 0.5);
+    /// assert_eq!(bloom.remove_property("_mix"), Some(String::from("0.5")));
+    /// assert!(bloom.properties.is_empty());
+    /// ```
+    pub fn remove_property(&mut self, key: &str) -> Option<String> {
+        let index = self
+            .properties
+            .iter()
+            .position(|(property, _)| property == key)?;
+        Some(self.properties.remove(index).1)
+    }
+
+    pub(crate) fn from_chunk(chunk: &rOBJ) -> RenderObject {
+        let mut render_object = RenderObject::new("");
+        render_object.read_order = ReadOrder::new(&chunk.attributes);
+        for (key, value) in chunk.attributes.pairs.iter() {
+            if key.content == "_type" {
+                render_object.object_type = value.content.clone();
+            } else {
+                render_object
+                    .properties
+                    .push((key.content.clone(), value.content.clone()));
+            }
+        }
+
+        render_object
+    }
+
+    pub(crate) fn to_chunk(&self) -> rOBJ {
+        let mut pairs = vec![(String::from("_type"), self.object_type.clone())];
+        pairs.extend(self.properties.iter().cloned());
+
+        rOBJ {
+            attributes: Dict::new(self.read_order.apply(pairs)),
+        }
+    }
+
+    pub(crate) fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        self.to_chunk().write(buf_writer)
+    }
+
+    //size in bytes when written including the chunk header
+    pub(crate) fn get_size(&self) -> i32 {
+        12 + self.to_chunk().get_size()
+    }
+}
+
+impl PartialEq for RenderObject {
+    fn eq(&self, other: &RenderObject) -> bool {
+        self.object_type == other.object_type && self.properties == other.properties
+    }
+}
+
+//sets the property if the value could be parsed. Returns false if it could not.
+fn set_parsed<T>(property: &mut Option<T>, value: Option<T>) -> bool {
+    let parsed = value.is_some();
+    if parsed {
+        *property = value;
+    }
+    parsed
+}
+
+fn parse_vector(string: &str) -> Option<(f32, f32, f32)> {
+    match parse_floats(string)[..] {
+        [Some(x), Some(y), Some(z)] => Some((x, y, z)),
+        _ => None,
+    }
+}
+
+fn vector_to_string(vector: (f32, f32, f32)) -> String {
+    format!("{} {} {}", vector.0, vector.1, vector.2)
+}
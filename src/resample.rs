@@ -0,0 +1,197 @@
+use crate::color::Color;
+use crate::model::Model;
+
+//the position of the model stays the same, so the voxels are scaled around its center
+impl Model {
+    /// Scales the voxels by the factor using the nearest voxel. Whole number factors make each
+    /// voxel into a block of voxels. It will return an error if the factor is not above 0 or the
+    /// scaled model would be larger than 256. VoxFile::add_scaled_model() can split larger
+    /// models.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(4, 4, 4);
+    /// model.add_voxel_at_pos(1, 0, 0, 1).unwrap();
+    /// model.scale(3.0).unwrap();
+    /// assert_eq!(model.size, (12, 12, 12));
+    /// assert_eq!(model.num_of_voxels(), 27);
+    /// assert!(model.is_voxel_at_pos(5, 2, 2));
+    ///
+    /// model.scale(0.5).unwrap();
+    /// assert_eq!(model.size, (6, 6, 6));
+    /// assert!(model.scale(100.0).is_err());
+    /// ```
+    pub fn scale(&mut self, factor: f32) -> Result<(), &str> {
+        if factor.is_nan() || factor <= 0.0 {
+            return Err("Scale must be greater than 0");
+        }
+        let size = self.scaled_size(factor);
+        if size.0 > 256 || size.1 > 256 || size.2 > 256 {
+            return Err("Scaled model would be larger than 256");
+        }
+
+        let mut scaled = Model::new(size.0 as u16, size.1 as u16, size.2 as u16);
+        scaled.set_storage(self.storage());
+        scaled
+            .fill_with(|x, y, z| self.scaled_get((x as u32, y as u32, z as u32), factor))
+            .unwrap();
+        self.size = scaled.size;
+        self.voxels = scaled.voxels;
+        Ok(())
+    }
+
+    /// Makes the model smaller by turning each block of voxels with the factor as its size into
+    /// one voxel. The block gets the color used the most in it, or is left empty if less than
+    /// half of it has voxels. It will return an error if the factor is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(8, 8, 8);
+    /// model.add_cube(0, 0, 0, 8, 8, 4, 1).unwrap();
+    /// model.add_cube(0, 0, 0, 2, 2, 1, 2).unwrap();
+    /// model.downsample(2).unwrap();
+    /// assert_eq!(model.size, (4, 4, 4));
+    /// assert_eq!(model.num_of_voxels(), 32);
+    /// //half of the block is color 2, so there is more of color 1
+    /// assert_eq!(model.get(0, 0, 0), Some(1));
+    /// ```
+    pub fn downsample(&mut self, factor: u8) -> Result<(), &str> {
+        self.downsample_with(factor, |colors| {
+            let mut counts = [0u32; 256];
+            for colorindex in colors {
+                counts[*colorindex as usize] += 1;
+            }
+            //the first color wins if there is a tie
+            let most = counts.iter().max().unwrap();
+            counts.iter().position(|count| count == most).unwrap() as u8
+        })
+    }
+
+    /// Like downsample() but the color of each block is the average of the colors in it, using
+    /// the closest color on the palette.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(2, 2, 2);
+    /// vox.set_palette_color(1, 255, 0, 0, 255);
+    /// vox.set_palette_color(2, 0, 0, 255, 255);
+    /// vox.set_palette_color(3, 128, 0, 128, 255);
+    /// vox.models[0].add_cube(0, 0, 0, 2, 2, 1, 1).unwrap();
+    /// vox.models[0].add_cube(0, 0, 1, 2, 2, 2, 2).unwrap();
+    ///
+    /// let palette = vox.palette;
+    /// vox.models[0].downsample_average(2, &palette).unwrap();
+    /// assert_eq!(vox.models[0].get(0, 0, 0), Some(3));
+    /// ```
+    pub fn downsample_average(&mut self, factor: u8, palette: &[Color; 256]) -> Result<(), &str> {
+        self.downsample_with(factor, |colors| {
+            let mut total = [0u32; 3];
+            for colorindex in colors {
+                let color = palette[*colorindex as usize - 1];
+                total[0] += color.r as u32;
+                total[1] += color.g as u32;
+                total[2] += color.b as u32;
+            }
+            let average = total.map(|value| (value / colors.len() as u32) as i32);
+
+            //palette entry i is color index i + 1 and the last entry is not used
+            let distance = |color: &Color| {
+                let (r, g, b) = (
+                    color.r as i32 - average[0],
+                    color.g as i32 - average[1],
+                    color.b as i32 - average[2],
+                );
+                r * r + g * g + b * b
+            };
+            let closest = palette[..255]
+                .iter()
+                .enumerate()
+                .min_by_key(|(_i, color)| distance(color))
+                .unwrap();
+            closest.0 as u8 + 1
+        })
+    }
+
+    //size of the model after scaling it by the factor
+    pub(crate) fn scaled_size(&self, factor: f32) -> (i64, i64, i64) {
+        let scaled = |size: u16| (size as f64 * factor as f64).round() as i64;
+        (
+            scaled(self.size.0),
+            scaled(self.size.1),
+            scaled(self.size.2),
+        )
+    }
+
+    //color index at a position of the model after scaling it by the factor
+    pub(crate) fn scaled_get(&self, position: (u32, u32, u32), factor: f32) -> Option<u8> {
+        let source = |value: u32, size: u16| {
+            let value = ((value as f32 + 0.5) / factor).floor() as u32;
+            value.min(size.saturating_sub(1) as u32) as u8
+        };
+        self.get(
+            source(position.0, self.size.0),
+            source(position.1, self.size.1),
+            source(position.2, self.size.2),
+        )
+    }
+
+    //makes each block into one voxel with the color from the closure. The closure gets the color
+    //indexes other than 0 in a block that is at least half full.
+    fn downsample_with<F>(&mut self, factor: u8, color: F) -> Result<(), &'static str>
+    where
+        F: Fn(&[u8]) -> u8 + Sync,
+    {
+        if factor == 0 {
+            return Err("Factor must be greater than 0");
+        }
+        let factor = factor as u16;
+        let size = (
+            self.size.0.div_ceil(factor),
+            self.size.1.div_ceil(factor),
+            self.size.2.div_ceil(factor),
+        );
+
+        let mut downsampled = Model::new(size.0, size.1, size.2);
+        downsampled.set_storage(self.storage());
+        let old_size = self.size;
+        downsampled
+            .fill_with(|x, y, z| {
+                let start = (x as u16 * factor, y as u16 * factor, z as u16 * factor);
+                let end = (
+                    (start.0 + factor).min(old_size.0),
+                    (start.1 + factor).min(old_size.1),
+                    (start.2 + factor).min(old_size.2),
+                );
+                let mut colors = Vec::new();
+                for x in start.0..end.0 {
+                    for y in start.1..end.1 {
+                        for z in start.2..end.2 {
+                            //color index 0 is empty space and has no palette entry
+                            match self.get(x as u8, y as u8, z as u8) {
+                                Some(0) | None => {}
+                                Some(colorindex) => colors.push(colorindex),
+                            }
+                        }
+                    }
+                }
+                let volume = (end.0 - start.0) as usize
+                    * (end.1 - start.1) as usize
+                    * (end.2 - start.2) as usize;
+                if colors.is_empty() || colors.len() * 2 < volume {
+                    None
+                } else {
+                    Some(color(&colors))
+                }
+            })
+            .unwrap();
+        self.size = downsampled.size;
+        self.voxels = downsampled.voxels;
+        Ok(())
+    }
+}
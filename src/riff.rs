@@ -1,14 +1,22 @@
+use crate::chunk::Chunk;
 use crate::convert::*;
-use crate::node::{Node, NodeAttributes, NodeType, Transform};
+use crate::error::VoxError;
+use crate::node::{Node, NodeAttributes, NodeFrame, NodeTransform, NodeType, Shape, ShapeModel};
+use crate::rotation::Rotation;
 use crate::writing::*;
-use std::convert::TryInto;
-use std::fs::File;
-use std::io::BufWriter;
-
-pub fn write_chunk(name: &str, size: u32, children_size: u32, writer: &mut BufWriter<File>) {
-    write_string_literal(writer, name);
-    write_slice(writer, &u32_to_array(size));
-    write_slice(writer, &u32_to_array(children_size));
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::io::Write;
+
+pub fn write_chunk<W: Write>(
+    name: &str,
+    size: u32,
+    children_size: u32,
+    writer: &mut W,
+) -> io::Result<()> {
+    write_string_literal(writer, name)?;
+    write_slice(writer, &u32_to_array(size))?;
+    write_slice(writer, &u32_to_array(children_size))
 }
 
 #[derive(Debug)]
@@ -18,24 +26,25 @@ pub struct VoxString {
 }
 
 impl VoxString {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> VoxString {
-        let size = i32::from_le_bytes(
-            input[(*cursor as usize)..(4 + *cursor as usize)]
-                .try_into()
-                .expect("failed to read"),
-        );
-        let string = String::from_utf8(
-            input[(4 + *cursor as usize)..((4 + size + *cursor) as usize)].to_vec(),
-        )
-        .unwrap();
-        *cursor = *cursor + 4 + size;
+    pub fn read(input: &[u8], cursor: &mut usize) -> Result<VoxString, VoxError> {
+        let offset = *cursor;
+        let size = read_i32(input, cursor)?;
+        if size < 0 {
+            return Err(VoxError::InvalidDict { offset });
+        }
+        let content_offset = *cursor;
+        let bytes = read_bytes(input, cursor, size as usize)?;
+        let string = String::from_utf8(bytes.to_vec()).map_err(|_| VoxError::InvalidUtf8 {
+            offset: content_offset,
+        })?;
 
-        VoxString::new(size, string)
+        Ok(VoxString::new(size, string))
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_slice(buf_writer, &self.size.to_le_bytes());
-        write_slice(buf_writer, self.content.as_bytes());
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_slice(buf_writer, &self.size.to_le_bytes())?;
+        write_slice(buf_writer, self.content.as_bytes())?;
+        Ok(())
     }
 
     pub fn new(size: i32, content: String) -> VoxString {
@@ -56,33 +65,57 @@ pub struct Dict {
 }
 
 impl Dict {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Dict {
+    pub fn new(pairs: Vec<(String, String)>) -> Dict {
+        Dict {
+            num_of_pairs: pairs.len() as i32,
+            pairs: pairs
+                .into_iter()
+                .map(|(key, value)| {
+                    (
+                        VoxString::new(key.len() as i32, key),
+                        VoxString::new(value.len() as i32, value),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    //returns all pairs with keys that are not in the list of known keys
+    pub fn other_pairs(&self, known_keys: &[&str]) -> Vec<(String, String)> {
+        self.pairs
+            .iter()
+            .filter(|(key, _)| !known_keys.contains(&key.content.as_str()))
+            .map(|(key, value)| (key.content.clone(), value.content.clone()))
+            .collect()
+    }
+
+    pub fn read(input: &[u8], cursor: &mut usize) -> Result<Dict, VoxError> {
         let mut pairs = Vec::new();
 
-        let size = i32::from_le_bytes(
-            input[(*cursor as usize)..(4 + *cursor as usize)]
-                .try_into()
-                .expect("failed to read"),
-        );
-        *cursor += 4;
+        let offset = *cursor;
+        let size = read_i32(input, cursor)?;
+        if size < 0 {
+            return Err(VoxError::InvalidDict { offset });
+        }
         for _i in 0..size {
-            let key = VoxString::read(input, cursor);
-            let value = VoxString::read(input, cursor);
+            let key = VoxString::read(input, cursor)?;
+            let value = VoxString::read(input, cursor)?;
             pairs.push((key, value))
         }
 
-        Dict {
+        Ok(Dict {
             num_of_pairs: size,
             pairs,
-        }
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_slice(buf_writer, &self.num_of_pairs.to_le_bytes());
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_slice(buf_writer, &self.num_of_pairs.to_le_bytes())?;
         for pair in self.pairs.iter() {
-            pair.0.write(buf_writer);
-            pair.1.write(buf_writer);
+            pair.0.write(buf_writer)?;
+            pair.1.write(buf_writer)?;
         }
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
@@ -105,99 +138,130 @@ pub struct nTRN {
     pub reserved_id: i32,
     //must be -1
     pub layer_id: i32,
-    //must be 1
     pub num_of_frames: i32,
     // for each frame
     // DICT	: frame attributes
     // (_r : int8) ROTATION, see (c)
     // (_t : int32x3) translation
+    // (_f : int32) frame index
     // }xN
-    pub frame_attributes: Dict,
+    pub frame_attributes: Vec<Dict>,
 }
 
 impl nTRN {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> nTRN {
-        *cursor += 12;
-        //need to make function for reading i32
-        let node_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let node_attributes = Dict::read(input, cursor);
-        let child_node_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let reserved_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let layer_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let num_of_frames = i32_from_vec(input, cursor);
-        *cursor += 4;
-
-        let frame_attributes = Dict::read(input, cursor);
-
-        nTRN {
-            node_id,
-            node_attributes,
-            child_node_id,
-            reserved_id,
-            layer_id,
-            num_of_frames,
-            frame_attributes,
-        }
+    pub fn read(chunk: &Chunk) -> Result<nTRN, VoxError> {
+        chunk.parse(|input, cursor| {
+            let node_id = read_i32(input, cursor)?;
+            let node_attributes = Dict::read(input, cursor)?;
+            let child_node_id = read_i32(input, cursor)?;
+            let reserved_id = read_i32(input, cursor)?;
+            let layer_id = read_i32(input, cursor)?;
+            let num_of_frames = read_i32(input, cursor)?;
+
+            let mut frame_attributes = Vec::new();
+            for _i in 0..num_of_frames {
+                frame_attributes.push(Dict::read(input, cursor)?);
+            }
+
+            Ok(nTRN {
+                node_id,
+                node_attributes,
+                child_node_id,
+                reserved_id,
+                layer_id,
+                num_of_frames,
+                frame_attributes,
+            })
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        //change
-        write_chunk("nTRN", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.node_id.to_le_bytes());
-        self.node_attributes.write(buf_writer);
-        write_slice(buf_writer, &self.child_node_id.to_le_bytes());
-        write_slice(buf_writer, &self.reserved_id.to_le_bytes());
-        write_slice(buf_writer, &self.layer_id.to_le_bytes());
-        write_slice(buf_writer, &self.num_of_frames.to_le_bytes());
-        self.frame_attributes.write(buf_writer);
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_chunk("nTRN", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.node_id.to_le_bytes())?;
+        self.node_attributes.write(buf_writer)?;
+        write_slice(buf_writer, &self.child_node_id.to_le_bytes())?;
+        write_slice(buf_writer, &self.reserved_id.to_le_bytes())?;
+        write_slice(buf_writer, &self.layer_id.to_le_bytes())?;
+        write_slice(buf_writer, &self.num_of_frames.to_le_bytes())?;
+        for frame in self.frame_attributes.iter() {
+            frame.write(buf_writer)?;
+        }
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
-        20 + self.node_attributes.get_size() + self.frame_attributes.get_size()
+        let mut size = 20 + self.node_attributes.get_size();
+        for frame in self.frame_attributes.iter() {
+            size += frame.get_size();
+        }
+        size
     }
 
-    pub fn to_node(&self) -> Node {
-        let data = Transform {
+    //offset is the start of the chunk and is used for errors
+    pub fn to_node(&self, offset: usize) -> Result<Node, VoxError> {
+        let mut frames = Vec::new();
+        for frame in self.frame_attributes.iter() {
+            frames.push(NodeFrame {
+                index: frame_index(frame, offset)?,
+                rotation: has_rotation(frame, offset)?,
+                translation: has_translation(frame, offset)?,
+                other_attributes: frame.other_pairs(&["_r", "_t", "_f"]),
+            });
+        }
+        let data = NodeTransform {
             layer: self.layer_id,
-            rotation: self.has_rotation(),
-            translation: self.has_translation(),
+            frames,
         };
 
-        Node::new(
+        Ok(Node::new(
             NodeType::Transform(data),
-            node_attributes_from_dict(&self.node_attributes),
-        )
+            node_attributes_from_dict(&self.node_attributes, offset)?,
+        ))
     }
+}
 
-    pub fn has_translation(&self) -> Option<(i32, i32, i32)> {
-        if !self.frame_attributes.pairs.is_empty() {
-            for attribute in self.frame_attributes.pairs.iter() {
-                if attribute.0.content == *"_t" {
-                    let parsed = parse_string(&attribute.1.content);
-                    return Some((parsed[0], parsed[1], parsed[2]));
-                }
-            }
+pub fn has_translation(dict: &Dict, offset: usize) -> Result<Option<(i32, i32, i32)>, VoxError> {
+    for attribute in dict.pairs.iter() {
+        if attribute.0.content == "_t" {
+            return match parse_string(&attribute.1.content)[..] {
+                [Some(x), Some(y), Some(z)] => Ok(Some((x, y, z))),
+                _ => Err(VoxError::InvalidDict { offset }),
+            };
         }
-
-        None
     }
 
-    pub fn has_rotation(&self) -> Option<i32> {
-        if !self.frame_attributes.pairs.is_empty() {
-            for attribute in self.frame_attributes.pairs.iter() {
-                if attribute.0.content == *"_r" {
-                    let parsed = parse_string(&attribute.1.content);
-                    return Some(parsed[0]);
-                }
-            }
+    Ok(None)
+}
+
+pub fn has_rotation(dict: &Dict, offset: usize) -> Result<Option<Rotation>, VoxError> {
+    for attribute in dict.pairs.iter() {
+        if attribute.0.content == "_r" {
+            return attribute
+                .1
+                .content
+                .parse()
+                .ok()
+                .and_then(Rotation::from_byte)
+                .map(Some)
+                .ok_or(VoxError::InvalidDict { offset });
         }
+    }
 
-        None
+    Ok(None)
+}
+
+//the _f key of a frame in a transform or shape node
+pub fn frame_index(dict: &Dict, offset: usize) -> Result<Option<u32>, VoxError> {
+    for attribute in dict.pairs.iter() {
+        if attribute.0.content == "_f" {
+            return match attribute.1.content.parse() {
+                Ok(index) => Ok(Some(index)),
+                Err(_) => Err(VoxError::InvalidDict { offset }),
+            };
+        }
     }
+
+    Ok(None)
 }
 
 //group node chunk
@@ -215,46 +279,45 @@ pub struct nGRP {
 }
 
 impl nGRP {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> nGRP {
-        *cursor += 12;
-        let node_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let node_attributes = Dict::read(input, cursor);
-        let num_of_children_nodes = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let mut child_id = Vec::new();
-        for _i in 0..num_of_children_nodes {
-            child_id.push(i32_from_vec(input, cursor));
-            *cursor += 4;
-        }
+    pub fn read(chunk: &Chunk) -> Result<nGRP, VoxError> {
+        chunk.parse(|input, cursor| {
+            let node_id = read_i32(input, cursor)?;
+            let node_attributes = Dict::read(input, cursor)?;
+            let num_of_children_nodes = read_i32(input, cursor)?;
+            let mut child_id = Vec::new();
+            for _i in 0..num_of_children_nodes {
+                child_id.push(read_i32(input, cursor)?);
+            }
 
-        nGRP {
-            node_id,
-            node_attributes,
-            num_of_children_nodes,
-            child_id,
-        }
+            Ok(nGRP {
+                node_id,
+                node_attributes,
+                num_of_children_nodes,
+                child_id,
+            })
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_chunk("nGRP", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.node_id.to_le_bytes());
-        self.node_attributes.write(buf_writer);
-        write_slice(buf_writer, &self.num_of_children_nodes.to_le_bytes());
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_chunk("nGRP", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.node_id.to_le_bytes())?;
+        self.node_attributes.write(buf_writer)?;
+        write_slice(buf_writer, &self.num_of_children_nodes.to_le_bytes())?;
         for child_id in self.child_id.iter() {
-            write_slice(buf_writer, &child_id.to_le_bytes());
+            write_slice(buf_writer, &child_id.to_le_bytes())?;
         }
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
         8 + self.node_attributes.get_size() + self.child_id.len() as i32 * 4
     }
 
-    pub fn to_node(&self) -> Node {
-        Node::new(
+    pub fn to_node(&self, offset: usize) -> Result<Node, VoxError> {
+        Ok(Node::new(
             NodeType::Group,
-            node_attributes_from_dict(&self.node_attributes),
-        )
+            node_attributes_from_dict(&self.node_attributes, offset)?,
+        ))
     }
 }
 
@@ -264,84 +327,100 @@ impl nGRP {
 pub struct nSHP {
     pub node_id: i32,
     pub node_attributes: Dict,
-    //must be 1
     pub num_of_models: i32,
     // for each model
     // {
     // int32	: model id
-    // DICT	: model attributes : reserved
+    // DICT	: model attributes
+    // (_f : int32) frame index
     // }xN
-    //only one model so only need one of each. may need to change if format changes
-    pub model_id: i32,
-    pub model_attributes: Dict,
+    pub models: Vec<(i32, Dict)>,
 }
 
 impl nSHP {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> nSHP {
-        *cursor += 12;
-        let node_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let node_attributes = Dict::read(input, cursor);
-        let num_of_models = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let model_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let model_attributes = Dict::read(input, cursor);
-
-        nSHP {
-            node_id,
-            node_attributes,
-            num_of_models,
-            model_id,
-            model_attributes,
-        }
+    pub fn read(chunk: &Chunk) -> Result<nSHP, VoxError> {
+        chunk.parse(|input, cursor| {
+            let node_id = read_i32(input, cursor)?;
+            let node_attributes = Dict::read(input, cursor)?;
+            let num_of_models = read_i32(input, cursor)?;
+            let mut models = Vec::new();
+            for _i in 0..num_of_models {
+                let model_id = read_i32(input, cursor)?;
+                models.push((model_id, Dict::read(input, cursor)?));
+            }
+
+            Ok(nSHP {
+                node_id,
+                node_attributes,
+                num_of_models,
+                models,
+            })
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_chunk("nSHP", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.node_id.to_le_bytes());
-        self.node_attributes.write(buf_writer);
-        write_slice(buf_writer, &self.num_of_models.to_le_bytes());
-        write_slice(buf_writer, &self.model_id.to_le_bytes());
-        self.model_attributes.write(buf_writer);
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_chunk("nSHP", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.node_id.to_le_bytes())?;
+        self.node_attributes.write(buf_writer)?;
+        write_slice(buf_writer, &self.num_of_models.to_le_bytes())?;
+        for (model_id, model_attributes) in self.models.iter() {
+            write_slice(buf_writer, &model_id.to_le_bytes())?;
+            model_attributes.write(buf_writer)?;
+        }
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
-        12 + self.node_attributes.get_size() + self.model_attributes.get_size()
+        let mut size = 8 + self.node_attributes.get_size();
+        for (_, model_attributes) in self.models.iter() {
+            size += 4 + model_attributes.get_size();
+        }
+        size
     }
 
-    pub fn to_node(&self) -> Node {
-        Node::new(
-            NodeType::Shape(self.model_id),
-            node_attributes_from_dict(&self.node_attributes),
-        )
+    pub fn to_node(&self, offset: usize) -> Result<Node, VoxError> {
+        let mut models = Vec::new();
+        for (model_id, model_attributes) in self.models.iter() {
+            models.push(ShapeModel {
+                model_id: *model_id,
+                index: frame_index(model_attributes, offset)?,
+                other_attributes: model_attributes.other_pairs(&["_f"]),
+            });
+        }
+        let shape = Shape { models };
+        Ok(Node::new(
+            NodeType::Shape(shape),
+            node_attributes_from_dict(&self.node_attributes, offset)?,
+        ))
     }
 }
 
 #[allow(non_camel_case_types)]
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug)]
 pub struct MATL {
-    material_id: i32,
-    properties: Dict,
+    pub material_id: i32,
+    pub properties: Dict,
 }
 
 impl MATL {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> MATL {
-        *cursor += 12;
-        let material_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let properties = Dict::read(input, cursor);
-
-        MATL {
-            material_id,
-            properties,
-        }
+    pub fn read(chunk: &Chunk) -> Result<MATL, VoxError> {
+        chunk.parse(|input, cursor| {
+            let material_id = read_i32(input, cursor)?;
+            let properties = Dict::read(input, cursor)?;
+
+            Ok(MATL {
+                material_id,
+                properties,
+            })
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_chunk("MATL", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.material_id.to_le_bytes());
-        self.properties.write(buf_writer);
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_chunk("MATL", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.material_id.to_le_bytes())?;
+        self.properties.write(buf_writer)?;
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
@@ -350,6 +429,7 @@ impl MATL {
 }
 
 #[allow(non_camel_case_types)]
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug)]
 pub struct LAYR {
     pub layer_id: i32,
@@ -359,26 +439,26 @@ pub struct LAYR {
 }
 
 impl LAYR {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> LAYR {
-        *cursor += 12;
-        let layer_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let layer_attributes = Dict::read(input, cursor);
-        let reserved_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-
-        LAYR {
-            layer_id,
-            layer_attributes,
-            reserved_id,
-        }
+    pub fn read(chunk: &Chunk) -> Result<LAYR, VoxError> {
+        chunk.parse(|input, cursor| {
+            let layer_id = read_i32(input, cursor)?;
+            let layer_attributes = Dict::read(input, cursor)?;
+            let reserved_id = read_i32(input, cursor)?;
+
+            Ok(LAYR {
+                layer_id,
+                layer_attributes,
+                reserved_id,
+            })
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_chunk("LAYR", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.layer_id.to_le_bytes());
-        self.layer_attributes.write(buf_writer);
-        write_slice(buf_writer, &self.reserved_id.to_le_bytes());
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_chunk("LAYR", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.layer_id.to_le_bytes())?;
+        self.layer_attributes.write(buf_writer)?;
+        write_slice(buf_writer, &self.reserved_id.to_le_bytes())?;
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
@@ -386,149 +466,256 @@ impl LAYR {
     }
 }
 
-//returns starting index. number 1 should return 1st chunk
-pub fn find_chunk(contents: &Vec<u8>, name: String, number: i32) -> Result<usize, ()> {
-    //currently breaks if can not find name
-    let mut chunk_name = String::new();
-    let mut chunk_size: u32;
-    let mut current_pos = 8;
-
-    let mut num_chunk = 1;
+//render object chunk
+#[allow(non_camel_case_types)]
+#[derive(Debug)]
+pub struct rOBJ {
+    pub attributes: Dict,
+}
 
-    while chunk_name != name || num_chunk != (number + 1) {
-        //gets name of chunk
-        chunk_name = String::from_utf8(
-            contents[(current_pos as usize)..((current_pos + 4) as usize)].to_vec(),
-        )
-        .expect("failed to create string");
+impl rOBJ {
+    pub fn read(chunk: &Chunk) -> Result<rOBJ, VoxError> {
+        chunk.parse(|input, cursor| {
+            Ok(rOBJ {
+                attributes: Dict::read(input, cursor)?,
+            })
+        })
+    }
 
-        if chunk_name == name {
-            if num_chunk == number {
-                return Ok(current_pos as usize);
-            }
-            num_chunk += 1;
-        }
-        current_pos += 4;
-        chunk_size = u32::from_le_bytes(
-            contents[(current_pos as usize)..((current_pos + 4) as usize)]
-                .try_into()
-                .expect("failed to read"),
-        );
-        current_pos += chunk_size + 8;
-        if current_pos >= contents.len() as u32 {
-            return Err(());
-        }
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_chunk("rOBJ", self.get_size() as u32, 0, buf_writer)?;
+        self.attributes.write(buf_writer)
     }
 
-    Err(())
+    pub fn get_size(&self) -> i32 {
+        self.attributes.get_size()
+    }
 }
 
-pub fn num_of_chunks(contents: &Vec<u8>, name: String) -> i32 {
-    let mut chunk_name;
-    let mut chunk_size: u32;
-    let mut current_pos: u32 = 8;
-
-    let mut num_of_chunks = 0;
+//render camera chunk
+#[allow(non_camel_case_types)]
+#[derive(Debug)]
+pub struct rCAM {
+    pub camera_id: i32,
+    pub attributes: Dict,
+}
 
-    while (current_pos as usize) < contents.len() {
-        //gets name of chunk
-        chunk_name = String::from_utf8(
-            contents[(current_pos as usize)..((current_pos + 4) as usize)].to_vec(),
-        )
-        .expect("failed to create string");
-        if chunk_name == name {
-            num_of_chunks += 1;
-        }
+impl rCAM {
+    pub fn read(chunk: &Chunk) -> Result<rCAM, VoxError> {
+        chunk.parse(|input, cursor| {
+            let camera_id = read_i32(input, cursor)?;
+            let attributes = Dict::read(input, cursor)?;
+
+            Ok(rCAM {
+                camera_id,
+                attributes,
+            })
+        })
+    }
 
-        current_pos += 4;
-        chunk_size = u32::from_le_bytes(
-            contents[(current_pos as usize)..((current_pos + 4) as usize)]
-                .try_into()
-                .expect("failed to read"),
-        );
-        current_pos += chunk_size + 8;
+    pub fn write<W: Write>(&self, buf_writer: &mut W) -> io::Result<()> {
+        write_chunk("rCAM", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.camera_id.to_le_bytes())?;
+        self.attributes.write(buf_writer)
     }
 
-    num_of_chunks
+    pub fn get_size(&self) -> i32 {
+        4 + self.attributes.get_size()
+    }
 }
 
-//returns root node
-pub fn nodes_from_chunks(input: &Vec<u8>) -> Node {
-    //start of root node
-    let mut cursor = find_chunk(input, String::from("nTRN"), 1).unwrap() as i32;
-    let root_node_chunk = nTRN::read(input, &mut cursor);
-    let mut root_node = root_node_chunk.to_node();
+//returns the next len bytes and moves the cursor past them
+pub fn read_bytes<'a>(
+    input: &'a [u8],
+    cursor: &mut usize,
+    len: usize,
+) -> Result<&'a [u8], VoxError> {
+    let end = match cursor.checked_add(len) {
+        Some(end) if end <= input.len() => end,
+        _ => return Err(VoxError::UnexpectedEof { offset: *cursor }),
+    };
+    let bytes = &input[*cursor..end];
+    *cursor = end;
+    Ok(bytes)
+}
 
-    add_node_children(&mut root_node, 1, &mut cursor, input);
+pub fn read_i32(input: &[u8], cursor: &mut usize) -> Result<i32, VoxError> {
+    let bytes = read_bytes(input, cursor, 4)?;
+    Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
 
-    root_node
+pub fn read_u32(input: &[u8], cursor: &mut usize) -> Result<u32, VoxError> {
+    let bytes = read_bytes(input, cursor, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
 }
 
-pub fn chunk_name(vec: &Vec<u8>, pos: &mut i32) -> String {
-    String::from_utf8(vec[(*pos as usize)..((*pos + 4) as usize)].to_vec())
-        .expect("failed to create string")
+//parses space separated numbers. None for any that are not numbers
+pub fn parse_string(string: &str) -> Vec<Option<i32>> {
+    string.split(' ').map(|num| num.parse().ok()).collect()
 }
 
-pub fn i32_from_vec(vec: &Vec<u8>, pos: &mut i32) -> i32 {
-    i32::from_le_bytes(
-        vec[(*pos as usize)..(4 + *pos as usize)]
-            .try_into()
-            .expect("failed to create i32"),
-    )
+//the pairs of a dictionary as they were read. Writing uses the same order and keeps the text of
+//values that were not changed, so "1.0" is not written back as "1".
+#[derive(Debug, Clone, Default)]
+pub struct ReadOrder(Vec<(String, String)>);
+
+impl ReadOrder {
+    pub fn new(dict: &Dict) -> ReadOrder {
+        ReadOrder(
+            dict.pairs
+                .iter()
+                .map(|(key, value)| (key.content.clone(), value.content.clone()))
+                .collect(),
+        )
+    }
+
+    //puts the pairs in the order they were read with pairs that were not read at the end
+    pub fn apply(&self, mut pairs: Vec<(String, String)>) -> Vec<(String, String)> {
+        let mut ordered = Vec::with_capacity(pairs.len());
+        for (key, read_value) in self.0.iter() {
+            if let Some(i) = pairs.iter().position(|(new_key, _)| new_key == key) {
+                let (key, value) = pairs.remove(i);
+                if same_value(read_value, &value) {
+                    ordered.push((key, read_value.clone()));
+                } else {
+                    ordered.push((key, value));
+                }
+            }
+        }
+        ordered.extend(pairs);
+        ordered
+    }
 }
 
-pub fn parse_string(string: &String) -> Vec<i32> {
-    let a: Vec<&str> = string.split(' ').collect();
-    let mut num: Vec<i32> = Vec::new();
-    a.iter()
-        .for_each(|string| num.push(string.parse().unwrap()));
+//checks if two values are the same text or the same numbers written differently
+fn same_value(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let (a, b) = (parse_floats(a), parse_floats(b));
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.is_some() && a == b)
+}
 
-    num
+//like parse_string but for numbers that can have a decimal point
+pub fn parse_floats(string: &str) -> Vec<Option<f32>> {
+    string.split(' ').map(|num| num.parse().ok()).collect()
 }
 
-pub fn node_attributes_from_dict(dict: &Dict) -> NodeAttributes {
+pub fn node_attributes_from_dict(dict: &Dict, offset: usize) -> Result<NodeAttributes, VoxError> {
     let mut name = None;
     let mut hidden = None;
     for pair in dict.pairs.iter() {
-        if pair.0.content == *"_hidden" {
-            hidden = if (pair.1.content.parse::<i32>().unwrap()) == 1 {
-                Some(true)
-            } else {
-                Some(false)
+        if pair.0.content == "_hidden" {
+            hidden = match pair.1.content.parse::<i32>() {
+                Ok(value) => Some(value == 1),
+                Err(_) => return Err(VoxError::InvalidDict { offset }),
             }
         }
 
-        if pair.0.content == *"_name" {
+        if pair.0.content == "_name" {
             name = Some(pair.1.content.clone())
         }
     }
 
-    NodeAttributes { name, hidden }
-}
-
-pub fn add_node_children(
-    node: &mut Node,
-    num_of_children: i32,
-    cursor: &mut i32,
-    contents: &Vec<u8>,
-) {
-    for _i in 0..num_of_children {
-        let name = chunk_name(contents, cursor);
-        if name == *"nTRN" {
-            let chunk = nTRN::read(contents, cursor);
-            let mut new_node = chunk.to_node();
-            add_node_children(&mut new_node, 1, cursor, contents);
-            node.add_child(new_node);
-        } else if name == *"nSHP" {
-            let chunk = nSHP::read(contents, cursor);
-            let new_node = chunk.to_node();
-            node.add_child(new_node);
-        } else if name == *"nGRP" {
-            let chunk = nGRP::read(contents, cursor);
-            let num_children = chunk.num_of_children_nodes;
-            let mut new_node = chunk.to_node();
-            add_node_children(&mut new_node, num_children, cursor, contents);
-            node.add_child(new_node);
+    Ok(NodeAttributes {
+        name,
+        hidden,
+        other_attributes: dict.other_pairs(&["_name", "_hidden"]),
+    })
+}
+
+//a scene graph chunk that has been read but not added to the tree yet
+enum NodeChunk {
+    Transform(nTRN),
+    Group(nGRP),
+    Shape(nSHP),
+}
+
+//returns root node which has id 0. Files without a scene graph give an empty group.
+pub fn nodes_from_chunks(chunks: &[Chunk]) -> Result<Node, VoxError> {
+    let mut nodes = HashMap::new();
+    for chunk in chunks.iter() {
+        let (node_id, node_chunk) = match &chunk.id {
+            b"nTRN" => {
+                let node = nTRN::read(chunk)?;
+                (node.node_id, NodeChunk::Transform(node))
+            }
+            b"nGRP" => {
+                let node = nGRP::read(chunk)?;
+                (node.node_id, NodeChunk::Group(node))
+            }
+            b"nSHP" => {
+                let node = nSHP::read(chunk)?;
+                (node.node_id, NodeChunk::Shape(node))
+            }
+            _ => continue,
+        };
+        nodes.insert(node_id, (chunk.offset, node_chunk));
+    }
+
+    match chunks.first() {
+        Some(first) => node_tree(&nodes, first.offset),
+        None => Ok(Node::new(NodeType::Group, NodeAttributes::new())),
+    }
+}
+
+//the deepest a node can be in the tree. Nodes are written, copied and dropped by recursion so
+//trees deeper than this are not read.
+const MAX_NODE_DEPTH: usize = 1024;
+
+//builds the tree starting at the node with id 0. Each node can only be reached once so nodes
+//that are their own parents or that have more than one parent are invalid.
+fn node_tree(
+    nodes: &HashMap<i32, (usize, NodeChunk)>,
+    first_offset: usize,
+) -> Result<Node, VoxError> {
+    let mut visited = HashSet::new();
+    //each entry is a node being built, its offset and the ids of children not added yet
+    let mut stack = vec![node_from_id(0, nodes, &mut visited, first_offset)?];
+    loop {
+        let (_, offset, child_ids) = stack.last_mut().unwrap();
+        let offset = *offset;
+        match child_ids.pop() {
+            Some(child_id) => {
+                if stack.len() >= MAX_NODE_DEPTH {
+                    return Err(VoxError::InvalidNode { offset });
+                }
+                stack.push(node_from_id(child_id, nodes, &mut visited, offset)?);
+            }
+            None => {
+                let (node, _, _) = stack.pop().unwrap();
+                match stack.last_mut() {
+                    Some((parent, _, _)) => parent.add_child(node),
+                    None => return Ok(node),
+                }
+            }
         }
     }
 }
+
+//makes the node with the id given without its children. The ids of the children are returned
+//in reverse so they can be popped in order.
+fn node_from_id(
+    id: i32,
+    nodes: &HashMap<i32, (usize, NodeChunk)>,
+    visited: &mut HashSet<i32>,
+    parent_offset: usize,
+) -> Result<(Node, usize, Vec<i32>), VoxError> {
+    let (offset, node_chunk) = match nodes.get(&id) {
+        Some((offset, node_chunk)) if visited.insert(id) => (*offset, node_chunk),
+        _ => {
+            return Err(VoxError::InvalidNode {
+                offset: parent_offset,
+            })
+        }
+    };
+
+    let (node, mut child_ids) = match node_chunk {
+        NodeChunk::Transform(chunk) => (chunk.to_node(offset)?, vec![chunk.child_node_id]),
+        NodeChunk::Group(chunk) => (chunk.to_node(offset)?, chunk.child_id.clone()),
+        NodeChunk::Shape(chunk) => (chunk.to_node(offset)?, vec![]),
+    };
+    child_ids.reverse();
+
+    Ok((node, offset, child_ids))
+}
@@ -0,0 +1,220 @@
+/// A rotation made of 90 degree turns and mirroring. There are 48 of these, one for each way the
+/// axes can be swapped around and flipped.
+///
+/// In .vox files rotations are stored as a single byte in the `_r` property. Bits 0 and 1 are the
+/// column of the non zero value in the first row of the matrix and bits 2 and 3 are the column in
+/// the second row. Bits 4, 5 and 6 are set when the value in the first, second or third row is -1.
+///
+/// # Example
+/// ```
+/// use create_vox::Rotation;
+///
+/// let quarter = Rotation::rotate_z90();
+/// let half = quarter.compose(quarter);
+/// assert_eq!(half.apply((1, 0, 0)), (-1, 0, 0));
+/// assert_eq!(half.compose(half), Rotation::IDENTITY);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rotation(u8);
+
+impl Rotation {
+    /// The rotation that does not change anything.
+    pub const IDENTITY: Rotation = Rotation(0b0000100);
+
+    /// Makes a rotation from the byte used in .vox files. Returns None if the byte is not a
+    /// valid rotation.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// assert_eq!(Rotation::from_byte(4), Some(Rotation::IDENTITY));
+    /// //both rows have their value in the first column
+    /// assert_eq!(Rotation::from_byte(0), None);
+    /// ```
+    pub fn from_byte(byte: u8) -> Option<Rotation> {
+        let first = byte & 0b11;
+        let second = (byte >> 2) & 0b11;
+        if byte >> 7 != 0 || first == 3 || second == 3 || first == second {
+            return None;
+        }
+
+        Some(Rotation(byte))
+    }
+
+    /// Returns the byte used for the rotation in .vox files.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// assert_eq!(Rotation::IDENTITY.to_byte(), 4);
+    /// ```
+    pub fn to_byte(self) -> u8 {
+        self.0
+    }
+
+    /// Makes a rotation from a matrix. Every row and column must have one value that is 1 or -1
+    /// with the rest being 0, otherwise None is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// let rotation = Rotation::from_matrix([[0, -1, 0], [1, 0, 0], [0, 0, 1]]).unwrap();
+    /// assert_eq!(rotation, Rotation::rotate_z90());
+    /// assert_eq!(Rotation::from_matrix([[1, 0, 0], [1, 0, 0], [0, 0, 1]]), None);
+    /// ```
+    pub fn from_matrix(matrix: [[i8; 3]; 3]) -> Option<Rotation> {
+        let mut columns = [0; 3];
+        let mut byte = 0;
+        for (row, values) in matrix.iter().enumerate() {
+            let mut column = None;
+            for (i, value) in values.iter().enumerate() {
+                match value {
+                    0 => {}
+                    1 | -1 if column.is_none() => column = Some(i),
+                    _ => return None,
+                }
+            }
+            let column = column?;
+            columns[row] = column;
+            if values[column] == -1 {
+                byte |= 1 << (4 + row);
+            }
+        }
+        if columns[0] == columns[1] || columns[0] == columns[2] || columns[1] == columns[2] {
+            return None;
+        }
+
+        Some(Rotation(byte | columns[0] as u8 | (columns[1] as u8) << 2))
+    }
+
+    /// Returns the rotation as a matrix. Multiplying a position by the matrix rotates it.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// assert_eq!(Rotation::IDENTITY.to_matrix(), [[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+    /// ```
+    pub fn to_matrix(self) -> [[i8; 3]; 3] {
+        let first = (self.0 & 0b11) as usize;
+        let second = ((self.0 >> 2) & 0b11) as usize;
+        let columns = [first, second, 3 - first - second];
+
+        let mut matrix = [[0; 3]; 3];
+        for (row, column) in columns.iter().enumerate() {
+            matrix[row][*column] = if self.0 & (1 << (4 + row)) != 0 {
+                -1
+            } else {
+                1
+            };
+        }
+        matrix
+    }
+
+    /// A 90 degree turn around the x axis. Y goes to z.
+    pub fn rotate_x90() -> Rotation {
+        Rotation::from_matrix([[1, 0, 0], [0, 0, -1], [0, 1, 0]]).unwrap()
+    }
+
+    /// A 90 degree turn around the y axis. Z goes to x.
+    pub fn rotate_y90() -> Rotation {
+        Rotation::from_matrix([[0, 0, 1], [0, 1, 0], [-1, 0, 0]]).unwrap()
+    }
+
+    /// A 90 degree turn around the z axis. X goes to y.
+    pub fn rotate_z90() -> Rotation {
+        Rotation::from_matrix([[0, -1, 0], [1, 0, 0], [0, 0, 1]]).unwrap()
+    }
+
+    /// Makes a rotation from a number of 90 degree turns around each axis. The turns around x are
+    /// done first, then y and then z. Negative turns go the other way.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// assert_eq!(Rotation::from_euler_90s(0, 0, 1), Rotation::rotate_z90());
+    /// assert_eq!(Rotation::from_euler_90s(4, -4, 8), Rotation::IDENTITY);
+    /// assert_eq!(Rotation::from_euler_90s(-1, 0, 0), Rotation::rotate_x90().inverse());
+    /// ```
+    pub fn from_euler_90s(x_turns: i32, y_turns: i32, z_turns: i32) -> Rotation {
+        let mut rotation = Rotation::IDENTITY;
+        let turns = [
+            (x_turns, Rotation::rotate_x90()),
+            (y_turns, Rotation::rotate_y90()),
+            (z_turns, Rotation::rotate_z90()),
+        ];
+        for (num_of_turns, turn) in turns.iter() {
+            for _i in 0..num_of_turns.rem_euclid(4) {
+                rotation = rotation.compose(*turn);
+            }
+        }
+        rotation
+    }
+
+    /// Returns the rotation that is the same as doing this rotation and then the other one.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// let rotation = Rotation::rotate_x90().compose(Rotation::rotate_z90());
+    /// assert_eq!(rotation.apply((0, 1, 0)), (0, 0, 1));
+    /// assert_eq!(rotation.apply((1, 0, 0)), (0, 1, 0));
+    /// ```
+    pub fn compose(self, other: Rotation) -> Rotation {
+        let first = self.to_matrix();
+        let second = other.to_matrix();
+        let mut matrix = [[0; 3]; 3];
+        for (row, values) in matrix.iter_mut().enumerate() {
+            for (column, value) in values.iter_mut().enumerate() {
+                *value = (0..3).map(|i| second[row][i] * first[i][column]).sum();
+            }
+        }
+        Rotation::from_matrix(matrix).unwrap()
+    }
+
+    /// Returns the rotation that undoes this one.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// let rotation = Rotation::from_euler_90s(1, 2, 3);
+    /// assert_eq!(rotation.compose(rotation.inverse()), Rotation::IDENTITY);
+    /// ```
+    pub fn inverse(self) -> Rotation {
+        let matrix = self.to_matrix();
+        let mut transposed = [[0; 3]; 3];
+        for (row, values) in matrix.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                transposed[column][row] = *value;
+            }
+        }
+        Rotation::from_matrix(transposed).unwrap()
+    }
+
+    /// Rotates a position around the origin.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// assert_eq!(Rotation::rotate_x90().apply((1, 2, 3)), (1, -3, 2));
+    /// ```
+    pub fn apply(self, position: (i32, i32, i32)) -> (i32, i32, i32) {
+        let matrix = self.to_matrix();
+        let position = [position.0, position.1, position.2];
+        let row = |row: [i8; 3]| (0..3).map(|i| row[i] as i32 * position[i]).sum();
+        (row(matrix[0]), row(matrix[1]), row(matrix[2]))
+    }
+}
+
+impl Default for Rotation {
+    fn default() -> Rotation {
+        Rotation::IDENTITY
+    }
+}
@@ -0,0 +1,222 @@
+//! Shapes made from signed distance functions. A signed distance function gives the distance from
+//! a position to the surface of a shape, which is negative inside of it. Shapes can be combined
+//! and changed before they are added to a model with [`Model::add_sdf`] or to a scene with
+//! [`VoxFile::add_sdf`](crate::VoxFile::add_sdf).
+//!
+//! # Example
+//! ```
+//! use create_vox::{sdf, Model};
+//!
+//! //a cube with a hole going through it and a ball on top
+//! let shape = sdf::cuboid((16.0, 16.0, 8.0), (8.0, 8.0, 8.0))
+//!     .subtract(sdf::capsule((16.0, 16.0, 0.0), (16.0, 16.0, 16.0), 4.0))
+//!     .smooth_union(sdf::sphere((16.0, 16.0, 20.0), 6.0), 3.0);
+//!
+//! let mut model = Model::new(32, 32, 32);
+//! model.add_sdf(&shape, 1).unwrap();
+//! assert!(!model.is_voxel_at_pos(16, 16, 8));
+//! assert!(model.is_voxel_at_pos(10, 10, 8));
+//! assert!(model.is_voxel_at_pos(16, 16, 25));
+//! ```
+
+use crate::model::Model;
+use crate::shapes::{dot, length, scale, sub};
+use std::sync::Arc;
+
+/// A shape given by its signed distance function. Positions are in voxels.
+#[derive(Clone)]
+pub struct Sdf {
+    distance: Arc<dyn Fn((f32, f32, f32)) -> f32 + Send + Sync>,
+}
+
+impl Sdf {
+    /// Makes a shape from a signed distance function.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::sdf::Sdf;
+    ///
+    /// //everything below z = 4
+    /// let ground = Sdf::new(|(_x, _y, z)| z - 4.0);
+    /// assert!(ground.distance((10.0, 3.0, 2.0)) < 0.0);
+    /// ```
+    pub fn new<F>(distance: F) -> Sdf
+    where
+        F: Fn((f32, f32, f32)) -> f32 + Send + Sync + 'static,
+    {
+        Sdf {
+            distance: Arc::new(distance),
+        }
+    }
+
+    /// Returns the distance from the position to the surface. It is negative inside the shape.
+    pub fn distance(&self, position: (f32, f32, f32)) -> f32 {
+        (self.distance)(position)
+    }
+
+    /// Both shapes together.
+    pub fn union(self, other: Sdf) -> Sdf {
+        Sdf::new(move |p| self.distance(p).min(other.distance(p)))
+    }
+
+    /// Cuts the other shape out of this one.
+    pub fn subtract(self, other: Sdf) -> Sdf {
+        Sdf::new(move |p| self.distance(p).max(-other.distance(p)))
+    }
+
+    /// Only the part where both shapes are.
+    pub fn intersect(self, other: Sdf) -> Sdf {
+        Sdf::new(move |p| self.distance(p).max(other.distance(p)))
+    }
+
+    /// Both shapes together with the corner between them rounded off. A larger smoothness
+    /// rounds more of the corner.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::sdf;
+    ///
+    /// let a = sdf::sphere((0.0, 0.0, 0.0), 4.0);
+    /// let b = sdf::sphere((8.0, 0.0, 0.0), 4.0);
+    /// //the middle is only filled in when the spheres are blended
+    /// assert!(a.clone().union(b.clone()).distance((4.0, 1.0, 0.0)) > 0.0);
+    /// assert!(a.smooth_union(b, 4.0).distance((4.0, 1.0, 0.0)) < 0.0);
+    /// ```
+    pub fn smooth_union(self, other: Sdf, smoothness: f32) -> Sdf {
+        let k = smoothness.max(f32::EPSILON);
+        Sdf::new(move |p| {
+            let (a, b) = (self.distance(p), other.distance(p));
+            let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+            b + (a - b) * h - k * h * (1.0 - h)
+        })
+    }
+
+    /// Moves the shape by the offset.
+    pub fn translate(self, offset: (f32, f32, f32)) -> Sdf {
+        Sdf::new(move |p| self.distance(sub(p, offset)))
+    }
+
+    /// Repeats the shape forever with the spacing on each axis. An axis with a spacing of 0 is
+    /// not repeated. Copies are placed around the origin, so the shape should be made around the
+    /// origin and then translated.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{sdf, Model};
+    ///
+    /// //a row of posts every 8 voxels
+    /// let posts = sdf::cuboid((0.0, 0.0, 0.0), (1.0, 1.0, 10.0))
+    ///     .repeat((8.0, 0.0, 0.0))
+    ///     .translate((4.0, 4.0, 0.0));
+    ///
+    /// let mut model = Model::new(64, 8, 8);
+    /// model.add_sdf(&posts, 1).unwrap();
+    /// assert!(model.is_voxel_at_pos(4, 4, 0));
+    /// assert!(model.is_voxel_at_pos(60, 4, 7));
+    /// assert!(!model.is_voxel_at_pos(8, 4, 0));
+    /// ```
+    pub fn repeat(self, spacing: (f32, f32, f32)) -> Sdf {
+        let wrap = |value: f32, spacing: f32| {
+            if spacing > 0.0 {
+                value - spacing * (value / spacing).round()
+            } else {
+                value
+            }
+        };
+        Sdf::new(move |p| {
+            self.distance((
+                wrap(p.0, spacing.0),
+                wrap(p.1, spacing.1),
+                wrap(p.2, spacing.2),
+            ))
+        })
+    }
+
+    /// Twists the shape around the z axis going through the origin. The angle is the number of
+    /// radians it turns for each voxel along z. The distance is not exact after twisting, so large
+    /// angles can leave small gaps.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{sdf, Model};
+    ///
+    /// let tower = sdf::cuboid((0.0, 0.0, 16.0), (6.0, 2.0, 16.0))
+    ///     .twist(std::f32::consts::FRAC_PI_2 / 32.0)
+    ///     .translate((8.0, 8.0, 0.0));
+    ///
+    /// let mut model = Model::new(16, 16, 32);
+    /// model.add_sdf(&tower, 1).unwrap();
+    /// //the bottom is along x and the top is along y
+    /// assert!(model.is_voxel_at_pos(13, 8, 0));
+    /// assert!(model.is_voxel_at_pos(8, 13, 31));
+    /// ```
+    pub fn twist(self, angle: f32) -> Sdf {
+        Sdf::new(move |p| {
+            let (sin, cos) = (-angle * p.2).sin_cos();
+            self.distance((cos * p.0 - sin * p.1, sin * p.0 + cos * p.1, p.2))
+        })
+    }
+}
+
+/// A sphere with the center and radius.
+pub fn sphere(center: (f32, f32, f32), radius: f32) -> Sdf {
+    Sdf::new(move |p| length(sub(p, center)) - radius)
+}
+
+/// A box with the center and half of its size on each axis. It is not called box since that is a
+/// keyword.
+///
+/// # Example
+/// ```
+/// use create_vox::sdf;
+///
+/// let cuboid = sdf::cuboid((5.0, 5.0, 5.0), (2.0, 3.0, 4.0));
+/// assert_eq!(cuboid.distance((5.0, 5.0, 10.0)), 1.0);
+/// ```
+pub fn cuboid(center: (f32, f32, f32), half_size: (f32, f32, f32)) -> Sdf {
+    Sdf::new(move |p| {
+        let q = (
+            (p.0 - center.0).abs() - half_size.0,
+            (p.1 - center.1).abs() - half_size.1,
+            (p.2 - center.2).abs() - half_size.2,
+        );
+        let outside = length((q.0.max(0.0), q.1.max(0.0), q.2.max(0.0)));
+        outside + q.0.max(q.1).max(q.2).min(0.0)
+    })
+}
+
+/// A line between two positions with rounded ends.
+pub fn capsule(start: (f32, f32, f32), end: (f32, f32, f32), radius: f32) -> Sdf {
+    Sdf::new(move |p| {
+        let (pa, ba) = (sub(p, start), sub(end, start));
+        let h = if dot(ba, ba) > 0.0 {
+            (dot(pa, ba) / dot(ba, ba)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        length(sub(pa, scale(ba, h))) - radius
+    })
+}
+
+impl Model {
+    /// Adds a voxel at every position of the model that is inside the shape. Parts of the shape
+    /// outside the model are left out.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{sdf, Model};
+    ///
+    /// let mut model = Model::new(20, 20, 20);
+    /// model.add_sdf(&sdf::sphere((10.0, 10.0, 10.0), 5.0), 1).unwrap();
+    /// assert!(model.is_voxel_at_pos(10, 10, 15));
+    /// ```
+    pub fn add_sdf(&mut self, sdf: &Sdf, colorindex: u8) -> Result<(), &str> {
+        self.fill_with(|x, y, z| {
+            if sdf.distance((x as f32, y as f32, z as f32)) <= 0.0 {
+                Some(colorindex)
+            } else {
+                None
+            }
+        })
+    }
+}
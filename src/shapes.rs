@@ -0,0 +1,348 @@
+use crate::model::Model;
+use crate::voxel::Voxel;
+
+//shapes are placed in voxel coordinates. A voxel is part of a shape if the position of the voxel
+//is inside it, so a sphere with a radius of 0 is a single voxel.
+impl Model {
+    /// Adds a sphere with the center and radius given. It will return an error if part of the
+    /// sphere is outside the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(20, 20, 20);
+    /// model.add_sphere((10.0, 10.0, 10.0), 5.0, 1).unwrap();
+    /// assert!(model.is_voxel_at_pos(10, 10, 15));
+    /// assert!(model.add_sphere((2.0, 2.0, 2.0), 5.0, 1).is_err());
+    /// ```
+    pub fn add_sphere(
+        &mut self,
+        center: (f32, f32, f32),
+        radius: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        self.add_hollow_sphere(center, radius, f32::INFINITY, colorindex)
+    }
+
+    /// Adds a sphere where only a wall with the thickness given is filled.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(20, 20, 20);
+    /// model.add_hollow_sphere((10.0, 10.0, 10.0), 8.0, 1.5, 1).unwrap();
+    /// assert!(!model.is_voxel_at_pos(10, 10, 10));
+    /// assert!(model.is_voxel_at_pos(10, 10, 18));
+    /// ```
+    pub fn add_hollow_sphere(
+        &mut self,
+        center: (f32, f32, f32),
+        radius: f32,
+        thickness: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        let size = (radius, radius, radius);
+        self.add_shape(center, size, thickness, colorindex, |p| length(p) - radius)
+    }
+
+    /// Adds an ellipsoid with a different radius on each axis. It will return an error if part of
+    /// the ellipsoid is outside the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(30, 20, 10);
+    /// model.add_ellipsoid((15.0, 10.0, 5.0), (12.0, 6.0, 3.0), 1).unwrap();
+    /// assert!(model.is_voxel_at_pos(27, 10, 5));
+    /// assert!(!model.is_voxel_at_pos(15, 10, 9));
+    /// ```
+    pub fn add_ellipsoid(
+        &mut self,
+        center: (f32, f32, f32),
+        radii: (f32, f32, f32),
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        self.add_hollow_ellipsoid(center, radii, f32::INFINITY, colorindex)
+    }
+
+    /// Adds an ellipsoid where only a wall with the thickness given is filled.
+    pub fn add_hollow_ellipsoid(
+        &mut self,
+        center: (f32, f32, f32),
+        radii: (f32, f32, f32),
+        thickness: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        self.add_shape(center, radii, thickness, colorindex, |p| {
+            ellipsoid(p, radii)
+        })
+    }
+
+    /// Adds a cylinder going from the center of one end to the center of the other. The ends can
+    /// be anywhere so the cylinder can be along any axis. It will return an error if part of the
+    /// cylinder is outside the model or if both ends are the same.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(20, 20, 20);
+    /// //a pillar going up from the floor
+    /// model.add_cylinder((10.0, 10.0, 0.0), (10.0, 10.0, 19.0), 3.0, 1).unwrap();
+    /// assert!(model.is_voxel_at_pos(13, 10, 19));
+    /// //a diagonal beam
+    /// model.add_cylinder((2.0, 2.0, 2.0), (17.0, 17.0, 17.0), 1.5, 2).unwrap();
+    /// ```
+    pub fn add_cylinder(
+        &mut self,
+        start: (f32, f32, f32),
+        end: (f32, f32, f32),
+        radius: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        self.add_hollow_cylinder(start, end, radius, f32::INFINITY, colorindex)
+    }
+
+    /// Adds a cylinder where only a wall with the thickness given is filled. The ends are closed.
+    pub fn add_hollow_cylinder(
+        &mut self,
+        start: (f32, f32, f32),
+        end: (f32, f32, f32),
+        radius: f32,
+        thickness: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        self.add_capped_cone(start, end, radius, radius, thickness, colorindex)
+    }
+
+    /// Adds a cone with its base centered on the base position and its point at the tip. It will
+    /// return an error if part of the cone is outside the model or if the base and tip are the
+    /// same.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(20, 20, 20);
+    /// model.add_cone((10.0, 10.0, 0.0), (10.0, 10.0, 15.0), 6.0, 1).unwrap();
+    /// assert!(model.is_voxel_at_pos(16, 10, 0));
+    /// assert!(model.is_voxel_at_pos(10, 10, 15));
+    /// assert!(!model.is_voxel_at_pos(12, 10, 14));
+    /// ```
+    pub fn add_cone(
+        &mut self,
+        base: (f32, f32, f32),
+        tip: (f32, f32, f32),
+        radius: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        self.add_hollow_cone(base, tip, radius, f32::INFINITY, colorindex)
+    }
+
+    /// Adds a cone where only a wall with the thickness given is filled. The base is closed.
+    pub fn add_hollow_cone(
+        &mut self,
+        base: (f32, f32, f32),
+        tip: (f32, f32, f32),
+        radius: f32,
+        thickness: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        self.add_capped_cone(base, tip, radius, 0.0, thickness, colorindex)
+    }
+
+    /// Adds a torus lying flat around the z axis. The major radius is from the center to the
+    /// middle of the ring and the minor radius is the radius of the ring. It will return an error
+    /// if part of the torus is outside the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut model = Model::new(30, 30, 10);
+    /// model.add_torus((15.0, 15.0, 5.0), 10.0, 3.0, 1).unwrap();
+    /// assert!(model.is_voxel_at_pos(25, 15, 5));
+    /// assert!(!model.is_voxel_at_pos(15, 15, 5));
+    /// ```
+    pub fn add_torus(
+        &mut self,
+        center: (f32, f32, f32),
+        major_radius: f32,
+        minor_radius: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        self.add_hollow_torus(
+            center,
+            major_radius,
+            minor_radius,
+            f32::INFINITY,
+            colorindex,
+        )
+    }
+
+    /// Adds a torus where only a wall with the thickness given is filled.
+    pub fn add_hollow_torus(
+        &mut self,
+        center: (f32, f32, f32),
+        major_radius: f32,
+        minor_radius: f32,
+        thickness: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        let outer = major_radius + minor_radius;
+        let size = (outer, outer, minor_radius);
+        self.add_shape(center, size, thickness, colorindex, |p| {
+            let ring = (p.0 * p.0 + p.1 * p.1).sqrt() - major_radius;
+            (ring * ring + p.2 * p.2).sqrt() - minor_radius
+        })
+    }
+
+    //cylinders are cones with the same radius at both ends
+    fn add_capped_cone(
+        &mut self,
+        start: (f32, f32, f32),
+        end: (f32, f32, f32),
+        start_radius: f32,
+        end_radius: f32,
+        thickness: f32,
+        colorindex: u8,
+    ) -> Result<(), &str> {
+        if start == end {
+            return Err("Start and end of shape are the same");
+        }
+
+        let center = scale(add(start, end), 0.5);
+        let radius = start_radius.max(end_radius);
+        let half = scale(sub(end, start), 0.5);
+        let size = (
+            half.0.abs() + radius,
+            half.1.abs() + radius,
+            half.2.abs() + radius,
+        );
+        let (a, b) = (scale(half, -1.0), half);
+        self.add_shape(center, size, thickness, colorindex, |p| {
+            capped_cone(p, a, b, start_radius, end_radius)
+        })
+    }
+
+    //fills the voxels where the distance to the shape is 0 or less and more than -thickness.
+    //The distance function gets the position relative to the center and size is how far the
+    //shape goes from the center on each axis.
+    fn add_shape<F>(
+        &mut self,
+        center: (f32, f32, f32),
+        size: (f32, f32, f32),
+        thickness: f32,
+        colorindex: u8,
+        distance: F,
+    ) -> Result<(), &'static str>
+    where
+        F: Fn((f32, f32, f32)) -> f32,
+    {
+        let min = (
+            (center.0 - size.0).ceil() as i32,
+            (center.1 - size.1).ceil() as i32,
+            (center.2 - size.2).ceil() as i32,
+        );
+        let max = (
+            (center.0 + size.0).floor() as i32,
+            (center.1 + size.1).floor() as i32,
+            (center.2 + size.2).floor() as i32,
+        );
+        let distance = &distance;
+        let positions = (min.0..=max.0).flat_map(move |x| {
+            (min.1..=max.1).flat_map(move |y| {
+                (min.2..=max.2).filter_map(move |z| {
+                    let p = (x as f32, y as f32, z as f32);
+                    let d = distance(sub(p, center));
+                    if d <= 0.0 && d > -thickness {
+                        Some((x, y, z))
+                    } else {
+                        None
+                    }
+                })
+            })
+        });
+
+        let size = self.size;
+        let outside = |(x, y, z): (i32, i32, i32)| {
+            x < 0
+                || y < 0
+                || z < 0
+                || x >= size.0 as i32
+                || y >= size.1 as i32
+                || z >= size.2 as i32
+        };
+        if positions.clone().any(outside) {
+            return Err("Shape does not fit inside the model");
+        }
+
+        self.place_voxels(
+            positions.map(|(x, y, z)| Voxel::new(x as u8, y as u8, z as u8, colorindex)),
+        )
+    }
+}
+
+pub(crate) fn add(a: (f32, f32, f32), b: (f32, f32, f32)) -> (f32, f32, f32) {
+    (a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+pub(crate) fn sub(a: (f32, f32, f32), b: (f32, f32, f32)) -> (f32, f32, f32) {
+    (a.0 - b.0, a.1 - b.1, a.2 - b.2)
+}
+
+pub(crate) fn scale(a: (f32, f32, f32), value: f32) -> (f32, f32, f32) {
+    (a.0 * value, a.1 * value, a.2 * value)
+}
+
+pub(crate) fn dot(a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+pub(crate) fn length(a: (f32, f32, f32)) -> f32 {
+    dot(a, a).sqrt()
+}
+
+//the distance is not exact but it is 0 on the surface
+fn ellipsoid(p: (f32, f32, f32), radii: (f32, f32, f32)) -> f32 {
+    let k0 = length((p.0 / radii.0, p.1 / radii.1, p.2 / radii.2));
+    if k0 == 0.0 {
+        return -radii.0.min(radii.1).min(radii.2);
+    }
+    let k1 = length((
+        p.0 / (radii.0 * radii.0),
+        p.1 / (radii.1 * radii.1),
+        p.2 / (radii.2 * radii.2),
+    ));
+    k0 * (k0 - 1.0) / k1
+}
+
+//distance to a cone between a and b with a radius at each end
+fn capped_cone(
+    p: (f32, f32, f32),
+    a: (f32, f32, f32),
+    b: (f32, f32, f32),
+    ra: f32,
+    rb: f32,
+) -> f32 {
+    let rba = rb - ra;
+    let ba = sub(b, a);
+    let pa = sub(p, a);
+    let baba = dot(ba, ba);
+    let papa = dot(pa, pa);
+    let paba = dot(pa, ba) / baba;
+    let x = (papa - paba * paba * baba).max(0.0).sqrt();
+    let cax = (x - if paba < 0.5 { ra } else { rb }).max(0.0);
+    let cay = (paba - 0.5).abs() - 0.5;
+    let k = rba * rba + baba;
+    let f = ((rba * (x - ra) + paba * baba) / k).clamp(0.0, 1.0);
+    let cbx = x - ra - f * rba;
+    let cby = paba - f;
+    let s = if cbx < 0.0 && cay < 0.0 { -1.0 } else { 1.0 };
+    s * (cax * cax + cay * cay * baba)
+        .min(cbx * cbx + cby * cby * baba)
+        .sqrt()
+}
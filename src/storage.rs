@@ -0,0 +1,273 @@
+use crate::voxel::Voxel;
+use std::collections::HashMap;
+
+/// How a model finds its voxels by position. With both there is only one voxel at a position.
+///
+/// # Example
+/// ```
+/// use create_vox::{Model, Storage};
+///
+/// let mut model = Model::new(64, 64, 64);
+/// model.set_storage(Storage::Dense);
+/// model.add_cube(0, 0, 0, 64, 64, 64, 1).unwrap();
+/// assert_eq!(model.storage(), Storage::Dense);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Storage {
+    /// A grid with a slot for every position in the model. It is fast for models that are mostly
+    /// filled but uses memory for the whole size of the model.
+    Dense,
+    /// A hash map of the positions that have voxels. It only uses memory for the voxels. This is
+    /// what models use if no storage is set.
+    #[default]
+    Hashed,
+}
+
+/// The voxels of a model with color indexes that can be changed. It is made by
+/// `Model::voxels_mut()`. When it is dropped voxels with a color index of 0 are removed.
+pub struct VoxelsMut<'a> {
+    voxels: &'a mut Voxels,
+}
+
+impl<'a> VoxelsMut<'a> {
+    pub(crate) fn new(voxels: &'a mut Voxels) -> VoxelsMut<'a> {
+        VoxelsMut { voxels }
+    }
+
+    /// Returns the position and color index of every voxel. Only the color indexes can be
+    /// changed so the positions stay unique.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((u8, u8, u8), &mut u8)> {
+        self.voxels.iter_mut()
+    }
+}
+
+impl<'a> Drop for VoxelsMut<'a> {
+    fn drop(&mut self) {
+        if self.voxels.iter().any(|voxel| voxel.colorindex == 0) {
+            self.voxels.retain(|voxel| voxel.colorindex != 0);
+        }
+    }
+}
+
+//voxels in the order they were added with an index to find them by position
+#[derive(Clone)]
+pub(crate) struct Voxels {
+    list: Vec<Voxel>,
+    index: Index,
+}
+
+#[derive(Clone)]
+enum Index {
+    //place in the list of each position. X changes the fastest, then y and then z.
+    Dense {
+        size: (usize, usize, usize),
+        slots: Vec<u32>,
+    },
+    Hashed(HashMap<(u8, u8, u8), u32>),
+}
+
+const EMPTY: u32 = u32::MAX;
+
+impl Index {
+    fn new(storage: Storage, size: (u16, u16, u16)) -> Index {
+        match storage {
+            Storage::Dense => {
+                let size = (size.0 as usize, size.1 as usize, size.2 as usize);
+                Index::Dense {
+                    size,
+                    slots: vec![EMPTY; size.0 * size.1 * size.2],
+                }
+            }
+            Storage::Hashed => Index::Hashed(HashMap::new()),
+        }
+    }
+
+    fn get(&self, position: (u8, u8, u8)) -> Option<usize> {
+        match self {
+            Index::Dense { size, slots } => match dense_slot(*size, position) {
+                Some(slot) if slots[slot] != EMPTY => Some(slots[slot] as usize),
+                _ => None,
+            },
+            Index::Hashed(map) => map.get(&position).map(|i| *i as usize),
+        }
+    }
+
+    //the dense grid must already be large enough for the position
+    fn set(&mut self, position: (u8, u8, u8), i: usize) {
+        match self {
+            Index::Dense { size, slots } => {
+                slots[dense_slot(*size, position).unwrap()] = i as u32;
+            }
+            Index::Hashed(map) => {
+                map.insert(position, i as u32);
+            }
+        }
+    }
+
+    fn remove(&mut self, position: (u8, u8, u8)) {
+        match self {
+            Index::Dense { size, slots } => {
+                if let Some(slot) = dense_slot(*size, position) {
+                    slots[slot] = EMPTY;
+                }
+            }
+            Index::Hashed(map) => {
+                map.remove(&position);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Index::Dense { slots, .. } => slots.iter_mut().for_each(|slot| *slot = EMPTY),
+            Index::Hashed(map) => map.clear(),
+        }
+    }
+
+    fn fits(&self, position: (u8, u8, u8)) -> bool {
+        match self {
+            Index::Dense { size, .. } => dense_slot(*size, position).is_some(),
+            Index::Hashed(_) => true,
+        }
+    }
+}
+
+fn dense_slot(size: (usize, usize, usize), position: (u8, u8, u8)) -> Option<usize> {
+    let (x, y, z) = (
+        position.0 as usize,
+        position.1 as usize,
+        position.2 as usize,
+    );
+    if x >= size.0 || y >= size.1 || z >= size.2 {
+        return None;
+    }
+    Some(x + y * size.0 + z * size.0 * size.1)
+}
+
+impl Voxels {
+    pub fn new(storage: Storage, size: (u16, u16, u16)) -> Voxels {
+        Voxels {
+            list: Vec::new(),
+            index: Index::new(storage, size),
+        }
+    }
+
+    pub fn storage(&self) -> Storage {
+        match self.index {
+            Index::Dense { .. } => Storage::Dense,
+            Index::Hashed(_) => Storage::Hashed,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn get(&self, position: (u8, u8, u8)) -> Option<&Voxel> {
+        self.index.get(position).map(|i| &self.list[i])
+    }
+
+    //replaces the voxel at the same position and returns it
+    pub fn insert(&mut self, voxel: Voxel) -> Option<Voxel> {
+        if let Some(i) = self.index.get(voxel.position) {
+            return Some(std::mem::replace(&mut self.list[i], voxel));
+        }
+
+        if !self.index.fits(voxel.position) {
+            //the size of the model was changed without set_size so the grid has to grow
+            let (x, y, z) = voxel.position;
+            let size = match self.index {
+                Index::Dense { size, .. } => size,
+                Index::Hashed(_) => (0, 0, 0),
+            };
+            let size = (
+                size.0.max(x as usize + 1) as u16,
+                size.1.max(y as usize + 1) as u16,
+                size.2.max(z as usize + 1) as u16,
+            );
+            self.rebuild(Storage::Dense, size);
+        }
+        self.index.set(voxel.position, self.list.len());
+        self.list.push(voxel);
+        None
+    }
+
+    pub fn remove(&mut self, position: (u8, u8, u8)) -> Option<Voxel> {
+        let i = self.index.get(position)?;
+        self.index.remove(position);
+        let voxel = self.list.swap_remove(i);
+        if let Some(moved) = self.list.get(i) {
+            self.index.set(moved.position, i);
+        }
+        Some(voxel)
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.index.clear();
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Voxel> {
+        self.list.iter()
+    }
+
+    //only the color indexes can be changed so the positions stay unique
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((u8, u8, u8), &mut u8)> {
+        self.list
+            .iter_mut()
+            .map(|voxel| (voxel.position, &mut voxel.colorindex))
+    }
+
+    pub fn retain<T>(&mut self, closure: T)
+    where
+        T: FnMut(&Voxel) -> bool,
+    {
+        self.list.retain(closure);
+        let storage = self.storage();
+        let size = self.dense_size();
+        self.rebuild(storage, size);
+    }
+
+    //changes the voxels with the closure. Voxels moved onto the same position replace the ones
+    //before them and voxels moved outside the size are removed.
+    pub fn change<T>(&mut self, closure: T, size: (u16, u16, u16))
+    where
+        T: FnMut(&mut Voxel),
+    {
+        self.list.iter_mut().for_each(closure);
+        let storage = self.storage();
+        self.rebuild(storage, size);
+    }
+
+    //makes a new index and removes voxels outside the size or with a color index of 0
+    pub fn rebuild(&mut self, storage: Storage, size: (u16, u16, u16)) {
+        let list = std::mem::take(&mut self.list);
+        self.index = Index::new(storage, size);
+        for voxel in list {
+            let (x, y, z) = voxel.position;
+            if voxel.colorindex == 0 {
+                continue;
+            }
+            if (x as u16) < size.0 && (y as u16) < size.1 && (z as u16) < size.2 {
+                self.insert(voxel);
+            }
+        }
+    }
+
+    //size of the dense grid or the largest size for the hash map
+    fn dense_size(&self) -> (u16, u16, u16) {
+        match self.index {
+            Index::Dense { size, .. } => (size.0 as u16, size.1 as u16, size.2 as u16),
+            Index::Hashed(_) => (256, 256, 256),
+        }
+    }
+}
+
+impl IntoIterator for Voxels {
+    type Item = Voxel;
+    type IntoIter = std::vec::IntoIter<Voxel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
+}
@@ -0,0 +1,47 @@
+use crate::rotation::Rotation;
+
+/// Where a copy of a model is placed and how it is shown. Properties that are None are not
+/// written.
+///
+/// # Example
+/// ```
+/// use create_vox::{Rotation, Transform};
+///
+/// let transform = Transform {
+///     translation: Some((10, 0, 5)),
+///     rotation: Some(Rotation::rotate_z90()),
+///     name: Some(String::from("fence")),
+///     ..Transform::default()
+/// };
+/// assert_eq!(transform.layer, None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Transform {
+    pub translation: Option<(i32, i32, i32)>,
+    pub rotation: Option<Rotation>,
+    pub layer: Option<i32>,
+    pub name: Option<String>,
+    pub hidden: Option<bool>,
+}
+
+/// Where a model or group is at a keyframe of an animation. Properties that are None are not
+/// written.
+///
+/// # Example
+/// ```
+/// use create_vox::{Keyframe, VoxFile};
+///
+/// let mut vox = VoxFile::new(10, 10, 10);
+/// vox.models[0].frames.push((
+///     10,
+///     Keyframe {
+///         translation: Some((0, 0, 20)),
+///         ..Keyframe::default()
+///     },
+/// ));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Keyframe {
+    pub translation: Option<(i32, i32, i32)>,
+    pub rotation: Option<Rotation>,
+}
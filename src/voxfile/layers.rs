@@ -0,0 +1,183 @@
+use crate::layer::Layer;
+use crate::model::Model;
+use crate::node::NodeType;
+use crate::voxfile::VoxFile;
+
+impl VoxFile {
+    /// Creates a new layer and returns the id that it has. The id is one more than the largest
+    /// id of the layers already in the voxfile.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].layer = Some(vox.add_layer(String::from("my layer"), false));
+    /// assert_eq!(vox.models[0].layer, Some(0));
+    /// ```
+    pub fn add_layer(&mut self, name: String, hidden: bool) -> i32 {
+        let id = self
+            .layers
+            .iter()
+            .map(|layer| layer.get_id() + 1)
+            .max()
+            .unwrap_or(0);
+        self.layers.push(Layer::new(name, hidden, id));
+        id
+    }
+
+    /// Returns all layers in the order they are written.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_layer(String::from("ground"), false);
+    /// vox.add_layer(String::from("sky"), true);
+    /// assert_eq!(vox.layers()[1].name, Some(String::from("sky")));
+    /// ```
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Like layers() but the layers can be changed.
+    pub fn layers_mut(&mut self) -> &mut [Layer] {
+        &mut self.layers
+    }
+
+    /// Returns the layer with the id given.
+    pub fn layer(&self, id: i32) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.get_id() == id)
+    }
+
+    /// Like layer() but the layer can be changed.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let id = vox.add_layer(String::from("ground"), false);
+    /// vox.layer_mut(id).unwrap().color = Some(Color::new(255, 0, 0, 255));
+    /// ```
+    pub fn layer_mut(&mut self, id: i32) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.get_id() == id)
+    }
+
+    /// Finds the first layer with the name given.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let id = vox.add_layer(String::from("ground"), false);
+    /// assert_eq!(vox.find_layer("ground").unwrap().get_id(), id);
+    /// ```
+    pub fn find_layer(&self, name: &str) -> Option<&Layer> {
+        self.layers
+            .iter()
+            .find(|layer| layer.name.as_deref() == Some(name))
+    }
+
+    /// Removes the layer with the id given and returns it. Models, copies and groups in the
+    /// layer are moved to layer 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_layer(String::from("ground"), false);
+    /// let trees = vox.add_layer(String::from("trees"), false);
+    /// vox.models[0].layer = Some(trees);
+    /// vox.remove_layer(trees);
+    /// assert_eq!(vox.models[0].layer, Some(0));
+    /// ```
+    pub fn remove_layer(&mut self, id: i32) -> Option<Layer> {
+        let index = self.layers.iter().position(|layer| layer.get_id() == id)?;
+        let removed = self.layers.remove(index);
+        self.change_layers(|layer| match layer {
+            Some(layer) if layer == id => Some(0),
+            layer => layer,
+        });
+        Some(removed)
+    }
+
+    /// Swaps the ids of two layers. Models, copies and groups stay in the same layer so they
+    /// also get the new id. This changes the order of the layers in MagicaVoxel.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let ground = vox.add_layer(String::from("ground"), false);
+    /// let sky = vox.add_layer(String::from("sky"), false);
+    /// vox.models[0].layer = Some(sky);
+    /// vox.swap_layers(ground, sky).unwrap();
+    /// assert_eq!(vox.layers()[0].name, Some(String::from("sky")));
+    /// assert_eq!(vox.models[0].layer, Some(ground));
+    /// ```
+    pub fn swap_layers(&mut self, id1: i32, id2: i32) -> Result<(), &str> {
+        if self.layer(id1).is_none() || self.layer(id2).is_none() {
+            return Err("layer does not exist");
+        }
+
+        for layer in self.layers.iter_mut() {
+            if layer.get_id() == id1 {
+                layer.set_id(id2);
+            } else if layer.get_id() == id2 {
+                layer.set_id(id1);
+            }
+        }
+        self.layers.sort_by_key(|layer| layer.get_id());
+        self.change_layers(|layer| match layer {
+            Some(layer) if layer == id1 => Some(id2),
+            Some(layer) if layer == id2 => Some(id1),
+            layer => layer,
+        });
+        Ok(())
+    }
+
+    /// Returns the models in the layer with the id given. Models without a layer are in layer 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_model(Model::new(5, 5, 5));
+    /// vox.models[1].layer = Some(2);
+    /// assert_eq!(vox.models_in_layer(0).count(), 1);
+    /// assert_eq!(vox.models_in_layer(2).count(), 1);
+    /// ```
+    pub fn models_in_layer(&self, id: i32) -> impl Iterator<Item = &Model> {
+        self.models
+            .iter()
+            .filter(move |model| model.layer.unwrap_or(0) == id)
+    }
+
+    //changes the layer of everything in the scene. The closure gets the old layer id.
+    fn change_layers<T>(&mut self, closure: T)
+    where
+        T: Fn(Option<i32>) -> Option<i32>,
+    {
+        let layers = self
+            .models
+            .iter_mut()
+            .map(|model| &mut model.layer)
+            .chain(self.copies.iter_mut().map(|copy| &mut copy.layer))
+            .chain(self.groups.iter_mut().map(|group| &mut group.layer));
+        for layer in layers {
+            *layer = closure(*layer);
+        }
+        //nodes that were loaded but are not used by this crate can also have a layer
+        for raw in self.raw_nodes.iter_mut() {
+            if let NodeType::Transform(transform) = &mut raw.node.node_type {
+                transform.layer = closure(Some(transform.layer)).unwrap_or(0);
+            }
+        }
+    }
+}
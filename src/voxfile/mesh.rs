@@ -0,0 +1,127 @@
+use crate::color::Color;
+use crate::mesh::{FillMode, Mesh};
+use crate::voxfile::VoxFile;
+use std::collections::HashSet;
+
+impl VoxFile {
+    /// Turns the mesh into new models. Meshes more than 256 voxels across are split into several
+    /// models which are positioned so they line up. The resolution is the number of voxels for
+    /// each unit of the mesh. Returns the indexes of the new models, or an error if the mesh is
+    /// too large to be placed in the scene or a vertex or the resolution is not a finite number.
+    ///
+    /// The palette is not overwritten. Each color of the mesh uses the same color on the palette
+    /// if there is one, otherwise an index that no voxel or material uses is set to it. When
+    /// there are no free indexes left the nearest color on the palette is used.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, FillMode, Mesh, Triangle, VoxFile};
+    ///
+    /// let mut mesh = Mesh::new();
+    /// mesh.triangles.push(Triangle::new([(0.0, 0.0, 0.0), (30.0, 0.0, 0.0), (0.0, 30.0, 0.0)], 1));
+    /// mesh.colors.push(Color::new(255, 0, 0, 255));
+    ///
+    /// let mut vox = VoxFile::new(1, 1, 1);
+    /// vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    /// //each unit is 10 voxels so the mesh is too large for one model. The corner away from
+    /// //the triangle has no voxels so it does not get a model.
+    /// let models = vox.add_mesh(&mesh, 10.0, FillMode::Surface).unwrap();
+    /// assert_eq!(models.len(), 3);
+    /// //index 1 is used by the first model so the red goes in index 2
+    /// assert_eq!(vox.get_palette_color(2), Color::new(255, 0, 0, 255));
+    /// assert_eq!(vox.models[models[0]].get(0, 0, 0), Some(2));
+    /// ```
+    pub fn add_mesh(
+        &mut self,
+        mesh: &Mesh,
+        resolution: f32,
+        mode: FillMode,
+    ) -> Result<Vec<usize>, &str> {
+        let min = match mesh.bounds() {
+            Some((min, _)) => min,
+            None => return Ok(vec![]),
+        };
+        //the size and position are checked before any voxels are made
+        mesh.check_finite(resolution)?;
+        let size = mesh
+            .voxel_size(resolution)
+            .ok_or("Mesh too large for the scene")?;
+        let offset = [min.0, min.1, min.2]
+            .iter()
+            .zip([size.0, size.1, size.2].iter())
+            .map(|(min, size)| {
+                let offset = (*min as f64 * resolution as f64).round();
+                if offset >= i32::MIN as f64 && offset + *size as f64 <= i32::MAX as f64 {
+                    Ok(offset as i32)
+                } else {
+                    Err("Mesh too large for the scene")
+                }
+            })
+            .collect::<Result<Vec<i32>, &str>>()?;
+
+        let colors = self.palette_indexes(mesh);
+        let voxels = mesh.voxelize(resolution, mode, size);
+        let min = (offset[0], offset[1], offset[2]);
+        let indexes = self.add_split(min, size, |x, y, z| {
+            voxels
+                .get(&(x, y, z))
+                .map(|colorindex| match colors.get(*colorindex as usize) {
+                    Some(Some(index)) => *index,
+                    _ => *colorindex,
+                })
+        });
+        Ok(indexes)
+    }
+
+    //the color index on the palette for each color index of the mesh. Only the colors used by
+    //the triangles are put into the palette.
+    fn palette_indexes(&mut self, mesh: &Mesh) -> Vec<Option<u8>> {
+        let mut used: HashSet<u8> = self
+            .models
+            .iter()
+            .flat_map(|model| model.voxels().map(|voxel| voxel.colorindex))
+            .chain(self.materials.keys().copied())
+            .collect();
+
+        let mut indexes = vec![None; mesh.colors.len().min(255) + 1];
+        for triangle in mesh.triangles.iter() {
+            let colorindex = triangle.colorindex as usize;
+            if colorindex == 0 || colorindex >= indexes.len() || indexes[colorindex].is_some() {
+                continue;
+            }
+
+            let color = mesh.colors[colorindex - 1];
+            let index = match (1..=255).find(|index| self.get_palette_color(*index) == color) {
+                Some(index) => index,
+                None => match (1..=255).find(|index| !used.contains(index)) {
+                    Some(index) => {
+                        self.set_palette_color(index, color.r, color.g, color.b, color.a);
+                        index
+                    }
+                    None => self.nearest_color(color),
+                },
+            };
+            used.insert(index);
+            indexes[colorindex] = Some(index);
+        }
+        indexes
+    }
+
+    //index on the palette of the color closest to the one given
+    fn nearest_color(&self, color: Color) -> u8 {
+        (1..=255)
+            .min_by_key(|index| {
+                let other = self.get_palette_color(*index);
+                [
+                    (color.r, other.r),
+                    (color.g, other.g),
+                    (color.b, other.b),
+                    (color.a, other.a),
+                ]
+                .iter()
+                .map(|(a, b)| (*a as i32 - *b as i32).pow(2))
+                .sum::<i32>()
+            })
+            .unwrap()
+    }
+}
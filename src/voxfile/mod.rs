@@ -1,6 +1,13 @@
 pub use voxfile::VoxFile;
 
+mod layers;
+mod mesh;
 mod palette;
 mod read;
+mod resample;
+mod scene;
+mod sdf;
+mod split;
+#[allow(clippy::module_inception)]
 mod voxfile;
 mod write;
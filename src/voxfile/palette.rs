@@ -1,4 +1,4 @@
-use crate::{Color, VoxFile};
+use crate::{Color, Material, VoxFile};
 
 impl VoxFile {
     /// sets the color of a index on the palette. The index used can not be 0.
@@ -108,6 +108,143 @@ impl VoxFile {
             self.palette[i as usize].a = a;
         }
     }
+
+    /// Sets the material used by an index on the palette. The index used can not be 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Material, MaterialType, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let mut metal = Material::new(MaterialType::Metal);
+    /// metal.roughness = Some(0.2);
+    /// vox.set_material(5, metal);
+    /// assert_eq!(vox.material(5).unwrap().material_type, MaterialType::Metal);
+    /// ```
+    pub fn set_material(&mut self, index: u8, material: Material) {
+        if index == 0 {
+            panic!("index needs to be between 1 and 255");
+        }
+        self.materials.insert(index, material);
+    }
+
+    /// Gets the material used by an index on the palette. Returns None if the index has no material.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let vox = VoxFile::new(10, 10, 10);
+    /// assert!(vox.material(1).is_none());
+    /// ```
+    pub fn material(&self, index: u8) -> Option<&Material> {
+        if index == 0 {
+            panic!("index needs to be between 1 and 255");
+        }
+        self.materials.get(&index)
+    }
+
+    /// Removes the material from an index on the palette and returns it.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Material, MaterialType, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.set_material(5, Material::new(MaterialType::Glass));
+    /// assert!(vox.remove_material(5).is_some());
+    /// assert!(vox.material(5).is_none());
+    /// ```
+    pub fn remove_material(&mut self, index: u8) -> Option<Material> {
+        if index == 0 {
+            panic!("index needs to be between 1 and 255");
+        }
+        self.materials.remove(&index)
+    }
+
+    /// Swaps two indexes on the palette. Voxels, materials and the index map are changed too so
+    /// the models look the same and the palette is shown in the same order.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.set_palette_color(1, 255, 0, 0, 255);
+    /// vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    /// vox.swap_palette_colors(1, 2);
+    ///
+    /// assert_eq!(vox.get_palette_color(2), Color::new(255, 0, 0, 255));
+    /// vox.models[0].change_voxels(|voxel| assert_eq!(voxel.colorindex, 2));
+    /// ```
+    pub fn swap_palette_colors(&mut self, index1: u8, index2: u8) {
+        if index1 == 0 || index2 == 0 {
+            panic!("index needs to be between 1 and 255");
+        }
+        let swap = |index: u8| {
+            if index == index1 {
+                index2
+            } else if index == index2 {
+                index1
+            } else {
+                index
+            }
+        };
+
+        self.palette
+            .swap((index1 - 1) as usize, (index2 - 1) as usize);
+        for model in self.models.iter_mut() {
+            model.change_voxels(|voxel| voxel.colorindex = swap(voxel.colorindex));
+        }
+
+        let material1 = self.materials.remove(&index1);
+        let material2 = self.materials.remove(&index2);
+        if let Some(material) = material1 {
+            self.materials.insert(index2, material);
+        }
+        if let Some(material) = material2 {
+            self.materials.insert(index1, material);
+        }
+
+        if let Some(index_map) = self.index_map.as_mut() {
+            for index in index_map.iter_mut() {
+                *index = swap(*index);
+            }
+        }
+    }
+
+    /// Sets the name of a row of 8 colors on the palette. Rows before it that have no name are
+    /// given an empty name.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.set_palette_note(2, "skin");
+    /// assert_eq!(vox.palette_note(2), Some("skin"));
+    /// assert_eq!(vox.palette_note(0), Some(""));
+    /// assert_eq!(vox.palette_note(3), None);
+    /// ```
+    pub fn set_palette_note(&mut self, row: usize, note: &str) {
+        if self.palette_notes.len() <= row {
+            self.palette_notes.resize(row + 1, String::new());
+        }
+        self.palette_notes[row] = String::from(note);
+    }
+
+    /// Gets the name of a row of 8 colors on the palette. Returns None if the row has no name.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// assert_eq!(vox.palette_note(0), None);
+    /// ```
+    pub fn palette_note(&self, row: usize) -> Option<&str> {
+        self.palette_notes.get(row).map(|note| note.as_str())
+    }
 }
 
 fn get_middle(a: u8, b: u8, point_between: f32) -> u8 {
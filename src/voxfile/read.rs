@@ -1,59 +1,200 @@
+use crate::chunk::{Chunk, ChunkReader};
+use crate::error::VoxError;
 use crate::layer::Layer;
+use crate::material::Material;
 use crate::model::Model;
+use crate::render::{Camera, RenderObject};
 use crate::riff;
-use crate::riff::{num_of_chunks, LAYR};
+use crate::riff::{rCAM, rOBJ, read_bytes, read_i32, read_u32, VoxString, LAYR, MATL};
+use crate::voxfile::voxfile::ChunkKind;
 use crate::voxfile::VoxFile;
 use crate::Color;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Read;
 
 impl VoxFile {
-    pub fn load(path: &str) -> VoxFile {
-        //read file
-        let mut file = File::open(path).expect("failed to open file");
-        let mut contents = Vec::new();
-        file.read_to_end(&mut contents)
-            .expect("failed to read contents");
+    /// Loads a .vox file. Returns an error if the file can not be read or is not a valid .vox file.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{VoxError, VoxFile};
+    ///
+    /// match VoxFile::load("does_not_exist.vox") {
+    ///     Err(VoxError::Io(_)) => {}
+    ///     _ => panic!("expected an io error"),
+    /// }
+    /// ```
+    pub fn load(path: &str) -> Result<VoxFile, VoxError> {
+        VoxFile::read_from(File::open(path)?)
+    }
 
-        //gets models
-        let num_of_models = riff::num_of_chunks(&contents, String::from("SIZE"));
-        let mut models = Vec::new();
+    /// Reads a .vox file from anything that implements Read, such as a file or network stream.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_voxel_at_pos(1, 2, 3, 4).unwrap();
+    /// let bytes = vox.to_bytes();
+    ///
+    /// let loaded = VoxFile::read_from(&bytes[..]).unwrap();
+    /// assert!(loaded.models[0].is_voxel_at_pos(1, 2, 3));
+    /// ```
+    pub fn read_from<R: Read>(mut reader: R) -> Result<VoxFile, VoxError> {
+        let mut contents = Vec::new();
+        reader.read_to_end(&mut contents)?;
+        VoxFile::from_bytes(&contents)
+    }
 
-        for i in 1..(num_of_models + 1) {
-            let mut chunk_pos = riff::find_chunk(&contents, String::from("SIZE"), i)
-                .expect("could not find SIZE chunk") as i32;
-            models.push(Model::read(&contents, &mut chunk_pos, i - 1));
+    /// Reads a .vox file that is already in memory. Files must start with `VOX ` and have a
+    /// version that is known, such as 150 or 200.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let vox = VoxFile::new(10, 20, 30);
+    /// let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    /// assert_eq!(loaded.models[0].size, (10, 20, 30));
+    /// ```
+    pub fn from_bytes(contents: &[u8]) -> Result<VoxFile, VoxError> {
+        let mut cursor = 0;
+        let magic = read_bytes(contents, &mut cursor, 4)?;
+        if magic != b"VOX " {
+            return Err(VoxError::BadMagic {
+                found: [magic[0], magic[1], magic[2], magic[3]],
+            });
+        }
+        let version = read_u32(contents, &mut cursor)?;
+        //older versions of this crate wrote 0 as the version
+        if version / 100 > 2 {
+            return Err(VoxError::UnsupportedVersion(version));
         }
 
-        //palette
-        let mut palette: [Color; 256] = [Color::new(0, 0, 0, 0); 256];
-        let current_pos = riff::find_chunk(&contents, String::from("RGBA"), 1).unwrap() + 12;
-        for i in 0..256 {
-            //gets the color data
-            let r: u8 = contents[(current_pos + (i * 4)) as usize];
-            let g: u8 = contents[(current_pos + (i * 4) + 1) as usize];
-            let b: u8 = contents[(current_pos + (i * 4) + 2) as usize];
-            let a: u8 = contents[(current_pos + (i * 4) + 3) as usize];
-            palette[i as usize] = Color::new(r, g, b, a);
+        //some writers, including older versions of this crate, wrote the wrong children size
+        //for MAIN so every chunk after it is read as one of its children
+        if read_bytes(contents, &mut cursor, 4)? != b"MAIN" {
+            return Err(VoxError::MissingChunk("MAIN"));
         }
+        let main_size = read_u32(contents, &mut cursor)? as usize;
+        read_bytes(contents, &mut cursor, 4)?;
+        read_bytes(contents, &mut cursor, main_size)?;
 
+        let mut models = Vec::new();
+        let mut size_chunk = None;
+        let mut palette = None;
+        let mut palette_notes = Vec::new();
+        let mut index_map = None;
         let mut layers = Vec::new();
-        for i in 1..(num_of_chunks(&contents, String::from("LAYR")) + 1) {
-            let mut chunk_pos = riff::find_chunk(&contents, String::from("LAYR"), i)
-                .expect("could not find SIZE chunk") as i32;
-            layers.push(Layer::from_chunk(LAYR::read(&contents, &mut chunk_pos)));
+        let mut node_chunks = Vec::new();
+        let mut materials = BTreeMap::new();
+        let mut render_objects = Vec::new();
+        let mut cameras = Vec::new();
+        let mut unknown_chunks = Vec::new();
+        let mut chunk_order = Vec::new();
+
+        for chunk in ChunkReader::from_range(contents, cursor, contents.len()) {
+            let chunk = chunk?;
+            let kind = match &chunk.id {
+                b"SIZE" | b"XYZI" => Some(ChunkKind::Model),
+                b"nTRN" | b"nGRP" | b"nSHP" => Some(ChunkKind::Node),
+                b"LAYR" => Some(ChunkKind::Layer),
+                b"RGBA" => Some(ChunkKind::Palette),
+                //added when the index of the material is read
+                b"MATL" => None,
+                b"rOBJ" => Some(ChunkKind::RenderObject),
+                b"rCAM" => Some(ChunkKind::Camera),
+                b"NOTE" => Some(ChunkKind::Notes),
+                b"IMAP" => Some(ChunkKind::IndexMap),
+                _ => Some(ChunkKind::Unknown),
+            };
+            chunk_order.extend(kind);
+            match &chunk.id {
+                b"SIZE" => size_chunk = Some(chunk),
+                //each XYZI chunk uses the SIZE chunk right before it
+                b"XYZI" => {
+                    let size = size_chunk.take().ok_or(VoxError::MissingChunk("SIZE"))?;
+                    models.push(Model::read(&size, &chunk, models.len() as i32)?);
+                }
+                b"RGBA" => palette = Some(read_palette(&chunk)?),
+                b"NOTE" => palette_notes = read_notes(&chunk)?,
+                b"IMAP" => index_map = Some(read_index_map(&chunk)?),
+                b"LAYR" => layers.push(Layer::from_chunk(LAYR::read(&chunk)?, chunk.offset)?),
+                b"nTRN" | b"nGRP" | b"nSHP" => node_chunks.push(chunk),
+                b"MATL" => {
+                    let material = MATL::read(&chunk)?;
+                    //MagicaVoxel uses ids 1 to 256 where 256 is index 0 on the palette
+                    if !(0..=256).contains(&material.material_id) {
+                        return Err(VoxError::InvalidChunk {
+                            offset: chunk.offset,
+                        });
+                    }
+                    let index = (material.material_id & 0xFF) as u8;
+                    chunk_order.push(ChunkKind::Material(index));
+                    materials.insert(index, Material::from_chunk(&material, chunk.offset)?);
+                }
+                b"rOBJ" => render_objects.push(RenderObject::from_chunk(&rOBJ::read(&chunk)?)),
+                b"rCAM" => cameras.push(Camera::from_chunk(&rCAM::read(&chunk)?)),
+                _ => unknown_chunks.push(chunk),
+            }
         }
 
         let mut voxfile = VoxFile {
+            version,
             models,
-            palette,
-            root_node: riff::nodes_from_chunks(&contents),
+            palette: palette.ok_or(VoxError::MissingChunk("RGBA"))?,
+            palette_notes,
+            index_map,
+            root_node: riff::nodes_from_chunks(&node_chunks)?,
             layers,
             copies: vec![],
+            groups: vec![],
+            raw_nodes: vec![],
+            materials,
+            render_objects,
+            cameras,
+            unknown_chunks,
+            chunk_order,
         };
 
         voxfile.get_node_data();
 
-        voxfile
+        Ok(voxfile)
     }
 }
+
+fn read_palette(chunk: &Chunk) -> Result<[Color; 256], VoxError> {
+    let mut palette: [Color; 256] = [Color::new(0, 0, 0, 0); 256];
+    chunk.parse(|input, cursor| {
+        let colors = read_bytes(input, cursor, 1024)?;
+        for (i, color) in colors.chunks(4).enumerate() {
+            palette[i] = Color::new(color[0], color[1], color[2], color[3]);
+        }
+        Ok(palette)
+    })
+}
+
+fn read_notes(chunk: &Chunk) -> Result<Vec<String>, VoxError> {
+    chunk.parse(|input, cursor| {
+        let offset = *cursor;
+        let num_of_notes = read_i32(input, cursor)?;
+        if num_of_notes < 0 {
+            return Err(VoxError::InvalidChunk { offset });
+        }
+        let mut notes = Vec::new();
+        for _i in 0..num_of_notes {
+            notes.push(VoxString::read(input, cursor)?.content);
+        }
+        Ok(notes)
+    })
+}
+
+fn read_index_map(chunk: &Chunk) -> Result<[u8; 256], VoxError> {
+    let mut index_map = [0; 256];
+    chunk.parse(|input, cursor| {
+        index_map.copy_from_slice(read_bytes(input, cursor, 256)?);
+        Ok(index_map)
+    })
+}
@@ -0,0 +1,71 @@
+use crate::model::Model;
+use crate::voxfile::split::check_area;
+use crate::voxfile::VoxFile;
+
+impl VoxFile {
+    /// Adds a copy of the model scaled by the factor like Model::scale(). If the scaled model is
+    /// larger than 256 it is split into several models which are positioned so they line up. The
+    /// center stays at the position of the model and the parts are turned around it by the
+    /// rotation of the model. Each part gets the rotation, layer, name and hidden of the model.
+    /// Returns the indexes of the new models, or an error if the factor is not above 0 or the
+    /// scaled model is too large to be placed in the scene.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut floor = Model::new(100, 100, 2);
+    /// floor.add_cube(0, 0, 0, 100, 100, 2, 1).unwrap();
+    ///
+    /// let mut vox = VoxFile::new(1, 1, 1);
+    /// let models = vox.add_scaled_model(&floor, 3.0).unwrap();
+    /// assert_eq!(models.len(), 4);
+    /// assert_eq!(vox.models[models[3]].size, (44, 44, 6));
+    /// ```
+    pub fn add_scaled_model(&mut self, model: &Model, factor: f32) -> Result<Vec<usize>, &str> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err("Scale must be greater than 0");
+        }
+        //done in i64 so large factors do not overflow
+        let size = model.scaled_size(factor);
+        let center = model.position.unwrap_or((0, 0, 0));
+        let min = (
+            center.0 as i64 - size.0 / 2,
+            center.1 as i64 - size.1 / 2,
+            center.2 as i64 - size.2 / 2,
+        );
+        check_area(min, size).map_err(|_| "Scaled model too large for the scene")?;
+        //a rotation can turn the parts so the longest side is along any axis
+        let half = size.0.max(size.1).max(size.2) / 2;
+        let rotated_in_range = [center.0, center.1, center.2].iter().all(|center| {
+            *center as i64 - half >= i32::MIN as i64 && *center as i64 + half <= i32::MAX as i64
+        });
+        if model.rotation.is_some() && !rotated_in_range {
+            return Err("Scaled model too large for the scene");
+        }
+        let min = (min.0 as i32, min.1 as i32, min.2 as i32);
+        let size = (size.0 as u32, size.1 as u32, size.2 as u32);
+
+        let indexes = self.add_split(min, size, |x, y, z| model.scaled_get((x, y, z), factor));
+        for i in indexes.iter() {
+            let part = &mut self.models[*i];
+            if let (Some(position), Some(rotation)) = (part.position, model.rotation) {
+                let offset = rotation.apply((
+                    position.0 - center.0,
+                    position.1 - center.1,
+                    position.2 - center.2,
+                ));
+                part.position = Some((
+                    center.0 + offset.0,
+                    center.1 + offset.1,
+                    center.2 + offset.2,
+                ));
+            }
+            part.rotation = model.rotation;
+            part.layer = model.layer;
+            part.name = model.name.clone();
+            part.hidden = model.hidden;
+        }
+        Ok(indexes)
+    }
+}
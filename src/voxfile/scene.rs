@@ -0,0 +1,424 @@
+use crate::copy::ModelCopy;
+use crate::group::Group;
+use crate::node::{Node, NodeAttributes, NodeExtras, NodeTransform, NodeType, RawNode};
+use crate::transform::Transform;
+use crate::voxfile::VoxFile;
+
+impl VoxFile {
+    //the nodes for everything in the scene, starting with the root transform
+    pub(in crate::voxfile) fn make_nodes(&self) -> Node {
+        //keeps the attributes of the root transform and group from a loaded file
+        let (mut root_node, mut group_node) = match &self.root_node.node_type {
+            NodeType::Transform(transform)
+                if self.root_node.children.len() == 1
+                    && self.root_node.children[0].node_type == NodeType::Group =>
+            {
+                (
+                    Node::new(
+                        NodeType::Transform(transform.clone()),
+                        self.root_node.attributes.clone(),
+                    ),
+                    Node::new(
+                        NodeType::Group,
+                        self.root_node.children[0].attributes.clone(),
+                    ),
+                )
+            }
+            _ => (
+                Node::new(
+                    NodeType::Transform(NodeTransform::default()),
+                    NodeAttributes::new(),
+                ),
+                Node::new(NodeType::Group, NodeAttributes::new()),
+            ),
+        };
+
+        group_node.children = self.group_nodes(None);
+        root_node.add_child(group_node);
+        root_node
+    }
+
+    //makes the nodes for everything in a group. Things that were loaded keep the order they
+    //had in the file and new things are put after them.
+    fn group_nodes(&self, group: Option<i32>) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        for model in self.models.iter().filter(|model| model.group == group) {
+            nodes.push((model.extras.order, model.to_node()));
+        }
+        for copy in self.copies.iter().filter(|copy| copy.group == group) {
+            nodes.push((copy.extras.order, copy.to_node()));
+        }
+        for child in self.groups.iter().filter(|child| child.parent == group) {
+            let mut node = child.to_node();
+            node.children[0].children = self.group_nodes(Some(child.id));
+            nodes.push((child.extras.order, node));
+        }
+        for raw in self.raw_nodes.iter().filter(|raw| raw.group == group) {
+            nodes.push((raw.order, raw.node.clone()));
+        }
+
+        nodes.sort_by_key(|(order, _)| (order.is_none(), *order));
+        nodes.into_iter().map(|(_, node)| node).collect()
+    }
+
+    //takes data from nodes and applies it to models, copies and groups
+    pub(crate) fn get_node_data(&mut self) {
+        let root_node = self.root_node.clone();
+        //the root transform has one group with everything in the scene inside it
+        let root_group = match root_node.node_type {
+            NodeType::Transform(_) if root_node.children.len() == 1 => &root_node.children[0],
+            _ => &root_node,
+        };
+
+        let mut used_model_ids = Vec::new();
+        self.read_group(root_group, None, &mut used_model_ids);
+    }
+
+    fn read_group(&mut self, group_node: &Node, group: Option<i32>, used_ids: &mut Vec<i32>) {
+        for (order, child) in group_node.children.iter().enumerate() {
+            if let Some(mut copy) = VoxFile::check_transform(child, order) {
+                copy.group = group;
+                //shapes of models that are not in the file are kept as copies so they are not lost
+                let has_model = self.models.iter().any(|model| model.id == copy.model_id);
+                if !has_model || used_ids.contains(&copy.model_id) {
+                    self.copies.push(copy);
+                } else {
+                    used_ids.push(copy.model_id);
+                    self.change_model_data(copy);
+                }
+            } else if let Some(mut child_group) = VoxFile::check_group(child, order) {
+                child_group.id = self.groups.len() as i32;
+                child_group.parent = group;
+                let id = child_group.id;
+                self.groups.push(child_group);
+                self.read_group(&child.children[0], Some(id), used_ids);
+            } else {
+                self.raw_nodes.push(RawNode {
+                    group,
+                    order: Some(order),
+                    node: child.clone(),
+                });
+            }
+        }
+    }
+
+    //returns the data of a transform node that has a shape node as its child
+    pub(crate) fn check_transform(transform_node: &Node, order: usize) -> Option<ModelCopy> {
+        let transform = match transform_node.node_type {
+            NodeType::Transform(ref transform) => transform,
+            _ => return None,
+        };
+
+        if !transform_node.has_child_shape() {
+            return None;
+        }
+        let shape_node = &transform_node.children[0];
+        let shape = match shape_node.node_type {
+            NodeType::Shape(ref shape) => shape,
+            _ => return None,
+        };
+
+        let first_model = shape.models.first()?;
+        let first_frame = transform.first_frame();
+
+        Some(ModelCopy {
+            model_id: first_model.model_id,
+            position: first_frame.translation,
+            rotation: first_frame.rotation,
+            layer: Some(transform.layer),
+            name: transform_node.attributes.name.clone(),
+            hidden: transform_node.attributes.hidden,
+            frames: transform.keyframes(),
+            model_frames: shape
+                .models
+                .iter()
+                .skip(1)
+                .map(|model| (model.index.unwrap_or(0), model.model_id))
+                .collect(),
+            group: None,
+            extras: NodeExtras::from_nodes(transform_node, transform, shape_node, order),
+        })
+    }
+
+    //returns the data of a transform node that has a group node as its child
+    fn check_group(transform_node: &Node, order: usize) -> Option<Group> {
+        let transform = match transform_node.node_type {
+            NodeType::Transform(ref transform) => transform,
+            _ => return None,
+        };
+        let group_node = match transform_node.children.first() {
+            Some(node) if node.node_type == NodeType::Group => node,
+            _ => return None,
+        };
+
+        let first_frame = transform.first_frame();
+
+        Some(Group {
+            position: first_frame.translation,
+            rotation: first_frame.rotation,
+            layer: Some(transform.layer),
+            name: transform_node.attributes.name.clone(),
+            hidden: transform_node.attributes.hidden,
+            frames: transform.keyframes(),
+            id: 0,
+            parent: None,
+            extras: NodeExtras::from_nodes(transform_node, transform, group_node, order),
+        })
+    }
+
+    //finds model by id and edits it with given data
+    pub(crate) fn change_model_data(&mut self, data: ModelCopy) {
+        for model in self.models.iter_mut() {
+            if model.id == data.model_id {
+                model.position = data.position;
+                model.layer = data.layer;
+                model.rotation = data.rotation;
+                model.name = data.name.clone();
+                model.hidden = data.hidden;
+                model.frames = data.frames.clone();
+                model.model_frames = data.model_frames.clone();
+                model.group = data.group;
+                model.extras = data.extras.clone();
+            }
+        }
+    }
+
+    /// Creates a new group inside the parent group and returns its id. A parent of None puts the
+    /// group in the root of the scene. It will panic if the parent group does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let town = vox.add_group(None, Transform::default());
+    /// let house = vox.add_group(
+    ///     Some(town),
+    ///     Transform {
+    ///         translation: Some((20, 0, 0)),
+    ///         name: Some(String::from("house")),
+    ///         ..Transform::default()
+    ///     },
+    /// );
+    /// vox.set_model_group(0, Some(house)).unwrap();
+    /// assert_eq!(vox.group(house).unwrap().parent(), Some(town));
+    /// ```
+    pub fn add_group(&mut self, parent: Option<i32>, transform: Transform) -> i32 {
+        if let Some(parent) = parent {
+            if self.group(parent).is_none() {
+                panic!("there is no group with id {}", parent)
+            }
+        }
+
+        let id = self
+            .groups
+            .iter()
+            .map(|group| group.id + 1)
+            .max()
+            .unwrap_or(0);
+        self.groups.push(Group::new(id, parent, transform));
+        id
+    }
+
+    /// Returns all groups in the scene.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_group(None, Transform::default());
+    /// assert_eq!(vox.groups().len(), 1);
+    /// ```
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    /// Gets the group with the id given.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let id = vox.add_group(None, Transform::default());
+    /// assert_eq!(vox.group(id).unwrap().get_id(), id);
+    /// ```
+    pub fn group(&self, id: i32) -> Option<&Group> {
+        self.groups.iter().find(|group| group.id == id)
+    }
+
+    /// Like group() but the group can be changed, such as to give it a name or hide it.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let id = vox.add_group(None, Transform::default());
+    /// let group = vox.group_mut(id).unwrap();
+    /// group.name = Some(String::from("trees"));
+    /// group.hidden = Some(true);
+    /// ```
+    pub fn group_mut(&mut self, id: i32) -> Option<&mut Group> {
+        self.groups.iter_mut().find(|group| group.id == id)
+    }
+
+    /// Finds the first group with the name given.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let transform = Transform {
+    ///     name: Some(String::from("trees")),
+    ///     ..Transform::default()
+    /// };
+    /// let id = vox.add_group(None, transform);
+    /// assert_eq!(vox.find_group("trees").unwrap().get_id(), id);
+    /// ```
+    pub fn find_group(&self, name: &str) -> Option<&Group> {
+        self.groups
+            .iter()
+            .find(|group| group.name.as_deref() == Some(name))
+    }
+
+    /// Moves a group and everything inside it into another group. A parent of None moves it to
+    /// the root of the scene. Returns an error if either group does not exist or if the group
+    /// would be moved inside itself.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let first = vox.add_group(None, Transform::default());
+    /// let second = vox.add_group(Some(first), Transform::default());
+    ///
+    /// assert!(vox.move_group(first, Some(second)).is_err());
+    /// vox.move_group(second, None).unwrap();
+    /// assert_eq!(vox.group(second).unwrap().parent(), None);
+    /// ```
+    pub fn move_group(&mut self, id: i32, parent: Option<i32>) -> Result<(), &str> {
+        if self.group(id).is_none() {
+            return Err("group does not exist");
+        }
+
+        //goes up from the new parent to make sure the group is not one of its parents
+        let mut current = parent;
+        while let Some(current_id) = current {
+            if current_id == id {
+                return Err("a group can not be moved inside itself");
+            }
+            current = match self.group(current_id) {
+                Some(group) => group.parent,
+                None => return Err("group does not exist"),
+            };
+        }
+
+        let group = self.group_mut(id).unwrap();
+        group.parent = parent;
+        group.extras.order = None;
+        Ok(())
+    }
+
+    /// Removes a group and returns it. Everything that was inside it is moved into the group it
+    /// was in.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let id = vox.add_group(None, Transform::default());
+    /// vox.set_model_group(0, Some(id)).unwrap();
+    ///
+    /// assert!(vox.remove_group(id).is_some());
+    /// assert_eq!(vox.models[0].group(), None);
+    /// ```
+    pub fn remove_group(&mut self, id: i32) -> Option<Group> {
+        let index = self.groups.iter().position(|group| group.id == id)?;
+        let group = self.groups.remove(index);
+
+        for model in self
+            .models
+            .iter_mut()
+            .filter(|model| model.group == Some(id))
+        {
+            model.group = group.parent;
+            model.extras.order = None;
+        }
+        for copy in self.copies.iter_mut().filter(|copy| copy.group == Some(id)) {
+            copy.group = group.parent;
+            copy.extras.order = None;
+        }
+        for child in self
+            .groups
+            .iter_mut()
+            .filter(|child| child.parent == Some(id))
+        {
+            child.parent = group.parent;
+            child.extras.order = None;
+        }
+        for raw in self
+            .raw_nodes
+            .iter_mut()
+            .filter(|raw| raw.group == Some(id))
+        {
+            raw.group = group.parent;
+            raw.order = None;
+        }
+
+        Some(group)
+    }
+
+    /// Moves a model into a group. The index is which model in the array of models to use and a
+    /// group of None moves it to the root of the scene. Returns an error if the group does not
+    /// exist.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let id = vox.add_group(None, Transform::default());
+    /// vox.set_model_group(0, Some(id)).unwrap();
+    /// assert!(vox.set_model_group(0, Some(id + 1)).is_err());
+    /// ```
+    pub fn set_model_group(&mut self, index: usize, group: Option<i32>) -> Result<(), &str> {
+        self.check_group_exists(group)?;
+        let model = &mut self.models[index];
+        model.group = group;
+        model.extras.order = None;
+        Ok(())
+    }
+
+    /// Moves a copy of a model into a group. A group of None moves it to the root of the scene.
+    /// Returns an error if the group does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let id = vox.add_group(None, Transform::default());
+    /// let copy = vox.add_model_copy_with(0, Transform::default());
+    /// vox.set_copy_group(copy, Some(id)).unwrap();
+    /// assert_eq!(vox.copies()[copy].group(), Some(id));
+    /// ```
+    pub fn set_copy_group(&mut self, index: usize, group: Option<i32>) -> Result<(), &str> {
+        self.check_group_exists(group)?;
+        let copy = &mut self.copies[index];
+        copy.group = group;
+        copy.extras.order = None;
+        Ok(())
+    }
+
+    fn check_group_exists(&self, group: Option<i32>) -> Result<(), &'static str> {
+        match group {
+            Some(id) if self.group(id).is_none() => Err("group does not exist"),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::sdf::Sdf;
+use crate::voxfile::split::check_area;
+use crate::voxfile::VoxFile;
+
+impl VoxFile {
+    /// Adds the part of the shape between the smallest and largest position as new models. The
+    /// area is split into models of up to 256 voxels on each side which are positioned so the
+    /// voxels are at the positions of the shape. Parts without any voxels are left out. Returns
+    /// the indexes of the new models, or an error if the largest position is below the smallest
+    /// or the area is too large to fill.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{sdf, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(1, 1, 1);
+    /// let planet = sdf::sphere((0.0, 0.0, 0.0), 200.0);
+    /// //a slice through the middle of the planet
+    /// let models = vox.add_sdf(&planet, (-200, -200, 0), (199, 199, 0), 1).unwrap();
+    /// assert_eq!(models.len(), 4);
+    /// ```
+    pub fn add_sdf(
+        &mut self,
+        sdf: &Sdf,
+        min: (i32, i32, i32),
+        max: (i32, i32, i32),
+        colorindex: u8,
+    ) -> Result<Vec<usize>, &str> {
+        //done in i64 so bounds far apart do not overflow
+        let size = (
+            max.0 as i64 - min.0 as i64 + 1,
+            max.1 as i64 - min.1 as i64 + 1,
+            max.2 as i64 - min.2 as i64 + 1,
+        );
+        if size.0 < 1 || size.1 < 1 || size.2 < 1 {
+            return Err("Largest position is below the smallest");
+        }
+        check_area((min.0 as i64, min.1 as i64, min.2 as i64), size)?;
+        let size = (size.0 as u32, size.1 as u32, size.2 as u32);
+        let indexes = self.add_split(min, size, |x, y, z| {
+            let position = (
+                (min.0 as i64 + x as i64) as f32,
+                (min.1 as i64 + y as i64) as f32,
+                (min.2 as i64 + z as i64) as f32,
+            );
+            if sdf.distance(position) <= 0.0 {
+                Some(colorindex)
+            } else {
+                None
+            }
+        });
+        Ok(indexes)
+    }
+}
@@ -0,0 +1,66 @@
+use crate::model::Model;
+use crate::voxfile::VoxFile;
+
+//the most voxels an area given to add_split can have, so a mistake in the size does not leave it
+//filling models for hours
+const MAX_AREA_VOXELS: i64 = 1 << 30;
+
+//checks that an area starting at min fits in the positions of the scene and is not too large to
+//fill, so the start and size can be given to add_split
+pub(crate) fn check_area(min: (i64, i64, i64), size: (i64, i64, i64)) -> Result<(), &'static str> {
+    let in_range = [(min.0, size.0), (min.1, size.1), (min.2, size.2)]
+        .iter()
+        .all(|(min, size)| {
+            *size >= 0 && *min >= i32::MIN as i64 && min + size - 1 <= i32::MAX as i64
+        });
+    if !in_range || size.0.saturating_mul(size.1).saturating_mul(size.2) > MAX_AREA_VOXELS {
+        return Err("Area too large for the scene");
+    }
+    Ok(())
+}
+
+impl VoxFile {
+    //adds an area starting at min as models of up to 256 voxels on each side. The closure gets
+    //the position in the area and returns the color index there. Models without voxels are left
+    //out. Returns the indexes of the new models.
+    pub(crate) fn add_split<F>(
+        &mut self,
+        min: (i32, i32, i32),
+        size: (u32, u32, u32),
+        closure: F,
+    ) -> Vec<usize>
+    where
+        F: Fn(u32, u32, u32) -> Option<u8> + Sync,
+    {
+        let mut indexes = Vec::new();
+        for z in (0..size.2).step_by(256) {
+            for y in (0..size.1).step_by(256) {
+                for x in (0..size.0).step_by(256) {
+                    let part_size = (
+                        (size.0 - x).min(256) as u16,
+                        (size.1 - y).min(256) as u16,
+                        (size.2 - z).min(256) as u16,
+                    );
+                    let mut model = Model::new(part_size.0, part_size.1, part_size.2);
+                    model
+                        .fill_with(|px, py, pz| {
+                            closure(x + px as u32, y + py as u32, z + pz as u32)
+                        })
+                        .unwrap();
+                    if model.num_of_voxels() == 0 {
+                        continue;
+                    }
+                    //the position of a model is where its center is
+                    model.position = Some((
+                        min.0 + (x + part_size.0 as u32 / 2) as i32,
+                        min.1 + (y + part_size.1 as u32 / 2) as i32,
+                        min.2 + (z + part_size.2 as u32 / 2) as i32,
+                    ));
+                    self.add_model(model);
+                    indexes.push(self.models.len() - 1);
+                }
+            }
+        }
+        indexes
+    }
+}
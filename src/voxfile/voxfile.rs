@@ -1,135 +1,96 @@
+use crate::chunk::Chunk;
 use crate::copy::ModelCopy;
+use crate::group::Group;
 use crate::layer::Layer;
+use crate::material::Material;
 use crate::model::Model;
-use crate::node::{Node, NodeAttributes, NodeType, Transform};
+use crate::node::{Node, NodeAttributes, NodeType, RawNode};
+use crate::render::{Camera, RenderObject};
+use crate::transform::Transform;
 use crate::Color;
+use std::collections::BTreeMap;
 
 /// Struct which holds all data for a .vox file such as models and palette
 pub struct VoxFile {
+    /// The version written after `VOX ` at the start of the file. When loading, this is the
+    /// version the file was saved with.
+    pub version: u32,
     pub models: Vec<Model>,
     pub palette: [Color; 256],
-    pub(crate)root_node: Node,
+    /// Names for the rows of the palette from the NOTE chunk. Each row is 8 colors.
+    pub palette_notes: Vec<String>,
+    /// The order the palette is shown in from the IMAP chunk. Entry i is the color index shown
+    /// at position i.
+    pub index_map: Option<[u8; 256]>,
+    pub(crate) root_node: Node,
     pub(crate) layers: Vec<Layer>,
     pub(crate) copies: Vec<ModelCopy>,
+    pub(crate) groups: Vec<Group>,
+    //nodes from a loaded file that are not models, copies or groups
+    pub(crate) raw_nodes: Vec<RawNode>,
+    //materials by index on the palette
+    pub(crate) materials: BTreeMap<u8, Material>,
+    /// Render settings such as lighting, fog and bloom.
+    pub render_objects: Vec<RenderObject>,
+    pub cameras: Vec<Camera>,
+    //chunks that this crate does not read
+    pub(crate) unknown_chunks: Vec<Chunk>,
+    //order of the chunks in the file that was loaded so they are written back in the same order
+    pub(crate) chunk_order: Vec<ChunkKind>,
+}
+
+//kind of chunk inside MAIN. Materials have the index on the palette they are for.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum ChunkKind {
+    Model,
+    Node,
+    Layer,
+    Palette,
+    Material(u8),
+    RenderObject,
+    Camera,
+    Notes,
+    IndexMap,
+    Unknown,
 }
 
 impl VoxFile {
-    //size in bytes when written
-    pub(in crate::voxfile) fn get_size(&self) -> i32 {
-        let mut size = 1024;
+    /// The version that new voxfiles are saved with. This is the same version MagicaVoxel uses.
+    pub const DEFAULT_VERSION: u32 = 200;
+
+    //size in bytes of all chunks inside MAIN when written
+    pub(in crate::voxfile) fn get_size(&self, root_node: &Node) -> i32 {
+        //RGBA chunk
+        let mut size = 12 + 1024;
         for model in self.models.iter() {
             size += model.get_size();
         }
         for layer in self.layers.iter() {
             size += layer.get_size()
         }
-        size += self.root_node.get_all_size();
-        size
-    }
-
-    pub(in crate::voxfile) fn make_nodes(&mut self) {
-        let mut root_node = Node::new(
-            NodeType::Transform(Transform::default()),
-            NodeAttributes::new(),
-        );
-        let mut group_node = Node::new(NodeType::Group, NodeAttributes::new());
-
-        for model in self.models.iter() {
-            group_node.add_child(model.to_node());
+        size += root_node.get_all_size();
+        for material in self.materials.values() {
+            size += material.get_size();
         }
-
-        for copy in self.copies.iter() {
-            group_node.add_child(copy.to_node());
+        for render_object in self.render_objects.iter() {
+            size += render_object.get_size();
         }
-        root_node.add_child(group_node);
-        self.root_node = root_node
-    }
-
-    //takes data from nodes and applies it to models
-    pub(crate) fn get_node_data(&mut self) {
-        let mut used_model_ids = Vec::new();
-        self.root_node
-            .clone()
-            .get_child_data_to_models(self, &mut used_model_ids)
-    }
-
-    //(id, pos, layer, rot, name)
-    pub(crate) fn check_transform(
-        transform_node: &Node,
-    ) -> Option<(
-        i32,
-        Option<(i32, i32, i32)>,
-        Option<i32>,
-        Option<u8>,
-        Option<String>,
-    )> {
-        let id: i32;
-        let pos: Option<(i32, i32, i32)>;
-        let layer: Option<i32>;
-        let rot: Option<u8>;
-
-        match transform_node.node_type {
-            NodeType::Transform(ref trans) => {
-                pos = trans.translation;
-                layer = Some(trans.layer);
-                rot = match trans.rotation {
-                    None => None,
-                    Some(rot) => Some(rot as u8),
-                };
-            }
-            _ => return None,
+        for camera in self.cameras.iter() {
+            size += camera.get_size();
         }
-
-        if transform_node.has_child_shape() {
-            match transform_node.children[0].node_type {
-                NodeType::Shape(shape_id) => {
-                    id = shape_id;
-                }
-                _ => return None,
+        if !self.palette_notes.is_empty() {
+            size += 16;
+            for note in self.palette_notes.iter() {
+                size += 4 + note.len() as i32;
             }
-        } else {
-            return None;
         }
-
-        let name = transform_node.attributes.name.clone();
-
-        Some((id, pos, layer, rot, name))
-    }
-
-    //finds model by id and edits it with given data
-    pub(crate) fn change_model_data(
-        &mut self,
-        id: i32,
-        pos: Option<(i32, i32, i32)>,
-        layer: Option<i32>,
-        rot: Option<u8>,
-        name: Option<String>,
-    ) {
-        for model in self.models.iter_mut() {
-            if model.id == id {
-                model.position = pos;
-                model.layer = layer;
-                model.rotation = rot;
-                model.name = name.clone();
-            }
+        if self.index_map.is_some() {
+            size += 12 + 256;
         }
-    }
-
-    pub(crate) fn add_copy(
-        &mut self,
-        id: i32,
-        pos: Option<(i32, i32, i32)>,
-        layer: Option<i32>,
-        rot: Option<u8>,
-        name: Option<String>,
-    ) {
-        self.copies.push(ModelCopy {
-            model_id: id,
-            position: pos,
-            rotation: rot,
-            layer,
-            name,
-        })
+        for chunk in self.unknown_chunks.iter() {
+            size += chunk.get_size();
+        }
+        size
     }
 
     /// creates a new voxfile with one model with the size given.
@@ -146,6 +107,7 @@ impl VoxFile {
             panic!("size can not be greater than 256")
         }
         VoxFile {
+            version: VoxFile::DEFAULT_VERSION,
             models: vec![Model::new(size_x, size_y, size_z)],
             palette: [Color {
                 r: 75,
@@ -153,16 +115,21 @@ impl VoxFile {
                 b: 75,
                 a: 255,
             }; 256],
+            palette_notes: vec![],
+            index_map: None,
             root_node: Node::new(NodeType::Group, NodeAttributes::new()),
             layers: vec![],
             copies: vec![],
+            groups: vec![],
+            raw_nodes: vec![],
+            materials: BTreeMap::new(),
+            render_objects: vec![],
+            cameras: vec![],
+            unknown_chunks: vec![],
+            chunk_order: vec![],
         }
     }
 
-    pub fn save(&mut self, file_path: &str) {
-        self.write(file_path);
-    }
-
     /// Add a copy of a model at a certain position. The model id is which model in the array of models to use.
     ///
     /// # Example
@@ -173,22 +140,143 @@ impl VoxFile {
     /// vox.add_model_copy(0, 5, 10, 5);
     /// ```
     pub fn add_model_copy(&mut self, model_id: i32, x: i32, y: i32, z: i32) {
-        self.add_copy(model_id, Some((x, y, z)), None, None, None);
+        let transform = Transform {
+            translation: Some((x, y, z)),
+            ..Transform::default()
+        };
+        self.add_model_copy_with(model_id, transform);
+    }
+
+    /// Adds a copy of a model with a transform for its position, rotation, layer, name and if it
+    /// is hidden. Returns the index of the copy.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Rotation, Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let index = vox.add_model_copy_with(
+    ///     0,
+    ///     Transform {
+    ///         translation: Some((20, 0, 5)),
+    ///         rotation: Some(Rotation::rotate_z90()),
+    ///         name: Some(String::from("fence")),
+    ///         ..Transform::default()
+    ///     },
+    /// );
+    /// assert_eq!(vox.copies()[index].rotation, Some(Rotation::rotate_z90()));
+    /// ```
+    pub fn add_model_copy_with(&mut self, model_id: i32, transform: Transform) -> usize {
+        self.copies.push(ModelCopy::new(model_id, transform));
+        self.copies.len() - 1
+    }
+
+    /// Returns all copies of models in the order they were added.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_model_copy(0, 5, 10, 5);
+    /// vox.add_model_copy(0, 20, 10, 5);
+    /// for copy in vox.copies() {
+    ///     assert_eq!(copy.model_id, 0);
+    /// }
+    /// ```
+    pub fn copies(&self) -> &[ModelCopy] {
+        &self.copies
+    }
+
+    /// Like copies() but the copies can be changed.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_model_copy(0, 5, 10, 5);
+    /// for copy in vox.copies_mut() {
+    ///     copy.hidden = Some(true);
+    /// }
+    /// ```
+    pub fn copies_mut(&mut self) -> &mut [ModelCopy] {
+        &mut self.copies
+    }
+
+    /// Returns all copies of the model with the id given.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_model(Model::new(5, 5, 5));
+    /// vox.add_model_copy(0, 5, 10, 5);
+    /// vox.add_model_copy(1, 20, 10, 5);
+    /// assert_eq!(vox.copies_of_model(1).count(), 1);
+    /// ```
+    pub fn copies_of_model(&self, model_id: i32) -> impl Iterator<Item = &ModelCopy> {
+        self.copies
+            .iter()
+            .filter(move |copy| copy.model_id == model_id)
     }
 
-    /// Creates a new layer and returns the id that it has.
+    /// Finds the first copy with the name given.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Transform, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let transform = Transform {
+    ///     name: Some(String::from("tree")),
+    ///     ..Transform::default()
+    /// };
+    /// vox.add_model_copy_with(0, transform);
+    /// assert!(vox.find_copy("tree").is_some());
+    /// assert!(vox.find_copy("rock").is_none());
+    /// ```
+    pub fn find_copy(&self, name: &str) -> Option<&ModelCopy> {
+        self.copies
+            .iter()
+            .find(|copy| copy.name.as_deref() == Some(name))
+    }
+
+    /// Removes the copy at the index given and returns it. Panics if the index is out of bounds.
     ///
     /// # Example
     /// ```
     /// use create_vox::VoxFile;
     ///
     /// let mut vox = VoxFile::new(10, 10, 10);
-    /// vox.models[0].layer = Some(vox.add_layer(String::from("my layer"), false));
+    /// vox.add_model_copy(0, 5, 10, 5);
+    /// let copy = vox.remove_copy(0);
+    /// assert_eq!(copy.position, Some((5, 10, 5)));
+    /// assert!(vox.copies().is_empty());
     /// ```
-    pub fn add_layer(&mut self, name: String, hidden: bool) -> i32 {
-        self.layers
-            .push(Layer::new(name, hidden, self.layers.len() as i32));
-        self.layers.len() as i32
+    pub fn remove_copy(&mut self, index: usize) -> ModelCopy {
+        self.copies.remove(index)
+    }
+
+    /// Removes all copies that the closure returns false for.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_model_copy(0, 5, 10, 5);
+    /// vox.add_model_copy(0, 50, 10, 5);
+    /// //removes copies that are far away
+    /// vox.retain_copies(|copy| copy.position.map_or(true, |pos| pos.0 < 20));
+    /// assert_eq!(vox.copies().len(), 1);
+    /// ```
+    pub fn retain_copies<T>(&mut self, closure: T)
+    where
+        T: FnMut(&ModelCopy) -> bool,
+    {
+        self.copies.retain(closure)
     }
 
     /// Changes the id of a model in the voxfile. If another model already has that id it will panic.
@@ -201,8 +289,8 @@ impl VoxFile {
     /// vox.change_model_id(0, 12);
     /// vox.add_model_copy(12, 20, 20, 20);
     /// ```
-    pub fn change_model_id(&mut self, index: i32, new_id: i32){
-        for model in self.models.iter(){
+    pub fn change_model_id(&mut self, index: i32, new_id: i32) {
+        for model in self.models.iter() {
             if model.id == new_id {
                 panic!("a model in this voxfile already has id {}", new_id)
             }
@@ -221,16 +309,16 @@ impl VoxFile {
     /// vox.add_model(Model::new(25,15,10));
     /// assert_eq!(vox.models.len(), 3);
     /// ```
-    pub fn add_model(&mut self, mut model: Model){
+    pub fn add_model(&mut self, mut model: Model) {
         model.id = self.get_new_model_id();
         self.models.push(model);
     }
 
-    fn get_new_model_id(&self) -> i32{
+    fn get_new_model_id(&self) -> i32 {
         let mut id = self.models.len() as i32;
 
         let mut current_ids = Vec::new();
-        for model in self.models.iter(){
+        for model in self.models.iter() {
             current_ids.push(model.id);
         }
 
@@ -238,6 +326,6 @@ impl VoxFile {
             id += 1;
         }
 
-        return id
+        id
     }
 }
@@ -1,30 +1,193 @@
-use crate::riff::write_chunk;
+use crate::error::VoxError;
+use crate::node::Node;
+use crate::riff::{write_chunk, VoxString};
+use crate::voxfile::voxfile::ChunkKind;
 use crate::voxfile::VoxFile;
 use crate::writing::{write_slice, write_string_literal};
+use std::collections::HashSet;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io;
+use std::io::{BufWriter, Write};
 
 impl VoxFile {
-    pub(crate) fn write(&mut self, path: &str) {
+    /// Saves the voxfile to the path given. Returns an error if the file could not be written.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 1).unwrap();
+    /// assert!(vox.save("/this/directory/does/not/exist/cube.vox").is_err());
+    /// ```
+    pub fn save(&self, file_path: &str) -> Result<(), VoxError> {
+        let file = File::create(file_path)?;
+        self.write_to(BufWriter::new(file))
+    }
+
+    /// Writes the voxfile to anything that implements Write. The writer is flushed at the end.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let vox = VoxFile::new(10, 10, 10);
+    /// let mut bytes = Vec::new();
+    /// vox.write_to(&mut bytes).unwrap();
+    /// assert_eq!(&bytes[0..4], b"VOX ");
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), VoxError> {
         //setups nodes for all children
-        self.make_nodes();
+        let mut root_node = self.make_nodes();
 
-        let file = File::create(path).expect("failed to create file");
-        let mut writer = BufWriter::new(file);
-        write_string_literal(&mut writer, "VOX ");
-        write_slice(&mut writer, &[0, 0, 0, 0]);
+        write_string_literal(&mut writer, "VOX ")?;
+        write_slice(&mut writer, &self.version.to_le_bytes())?;
 
-        write_chunk("MAIN", 0, self.get_size() as u32, &mut writer);
-        for model in self.models.iter() {
-            model.write(&mut writer);
-        }
-        self.root_node.write_all(&mut writer);
-        for layer in self.layers.iter() {
-            layer.write(&mut writer);
+        write_chunk("MAIN", 0, self.get_size(&root_node) as u32, &mut writer)?;
+        //chunks are written in the order they were loaded in. Anything else is written after
+        //them in the order MagicaVoxel uses.
+        let mut written = Written::default();
+        let default_order = [
+            ChunkKind::Model,
+            ChunkKind::Node,
+            ChunkKind::Layer,
+            ChunkKind::Palette,
+        ];
+        let mut order: Vec<ChunkKind> = self.chunk_order.clone();
+        order.extend(default_order.iter());
+        //materials are written from index 1 to 255 and then index 0
+        order.extend(
+            self.materials
+                .keys()
+                .filter(|index| **index != 0)
+                .chain(self.materials.keys().filter(|index| **index == 0))
+                .map(|index| ChunkKind::Material(*index)),
+        );
+        order.extend(self.render_objects.iter().map(|_| ChunkKind::RenderObject));
+        order.extend(self.cameras.iter().map(|_| ChunkKind::Camera));
+        order.extend([ChunkKind::Notes, ChunkKind::IndexMap].iter());
+        order.extend(self.unknown_chunks.iter().map(|_| ChunkKind::Unknown));
+
+        for kind in order {
+            self.write_kind(kind, &mut root_node, &mut written, &mut writer)?;
         }
-        write_chunk("RGBA", 1024, 0, &mut writer);
-        for color in self.palette.iter() {
-            write_slice(&mut writer, &[color.r, color.g, color.b, color.a])
+
+        //flush here so errors are returned instead of being lost when the writer is dropped
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the voxfile as the bytes of a .vox file.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let vox = VoxFile::new(10, 10, 10);
+    /// let bytes = vox.to_bytes();
+    /// assert_eq!(&bytes[0..4], b"VOX ");
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes)
+            .expect("writing to a Vec can not fail");
+        bytes
+    }
+
+    //writes the next chunk of the kind that has not been written yet. Models, nodes and layers
+    //are all written together.
+    fn write_kind<W: Write>(
+        &self,
+        kind: ChunkKind,
+        root_node: &mut Node,
+        written: &mut Written,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        match kind {
+            ChunkKind::Model if !written.models => {
+                written.models = true;
+                for model in self.models.iter() {
+                    model.write(writer)?;
+                }
+            }
+            ChunkKind::Node if !written.nodes => {
+                written.nodes = true;
+                root_node.write_all(writer)?;
+            }
+            ChunkKind::Layer if !written.layers => {
+                written.layers = true;
+                for layer in self.layers.iter() {
+                    layer.write(writer)?;
+                }
+            }
+            ChunkKind::Palette if !written.palette => {
+                written.palette = true;
+                write_chunk("RGBA", 1024, 0, writer)?;
+                for color in self.palette.iter() {
+                    write_slice(writer, &[color.r, color.g, color.b, color.a])?;
+                }
+            }
+            ChunkKind::Material(index) if !written.materials.contains(&index) => {
+                if let Some(material) = self.materials.get(&index) {
+                    written.materials.insert(index);
+                    material.write(index, writer)?;
+                }
+            }
+            ChunkKind::RenderObject => {
+                if let Some(render_object) = self.render_objects.get(written.render_objects) {
+                    written.render_objects += 1;
+                    render_object.write(writer)?;
+                }
+            }
+            ChunkKind::Camera => {
+                if let Some(camera) = self.cameras.get(written.cameras) {
+                    written.cameras += 1;
+                    camera.write(writer)?;
+                }
+            }
+            ChunkKind::Notes if !written.notes && !self.palette_notes.is_empty() => {
+                written.notes = true;
+                let size = self
+                    .palette_notes
+                    .iter()
+                    .map(|note| 4 + note.len() as u32)
+                    .sum::<u32>();
+                write_chunk("NOTE", 4 + size, 0, writer)?;
+                write_slice(writer, &(self.palette_notes.len() as i32).to_le_bytes())?;
+                for note in self.palette_notes.iter() {
+                    VoxString::new(note.len() as i32, note.clone()).write(writer)?;
+                }
+            }
+            ChunkKind::IndexMap if !written.index_map => {
+                if let Some(index_map) = &self.index_map {
+                    written.index_map = true;
+                    write_chunk("IMAP", 256, 0, writer)?;
+                    write_slice(writer, index_map)?;
+                }
+            }
+            ChunkKind::Unknown => {
+                if let Some(chunk) = self.unknown_chunks.get(written.unknown_chunks) {
+                    written.unknown_chunks += 1;
+                    chunk.write(writer)?;
+                }
+            }
+            _ => {}
         }
+        Ok(())
     }
 }
+
+//chunks that have been written so far
+#[derive(Default)]
+struct Written {
+    models: bool,
+    nodes: bool,
+    layers: bool,
+    palette: bool,
+    materials: HashSet<u8>,
+    render_objects: usize,
+    cameras: usize,
+    notes: bool,
+    index_map: bool,
+    unknown_chunks: usize,
+}
@@ -1,12 +1,9 @@
 use std::io::Write;
 
-pub(crate) fn write_string_literal(
-    inputfile: &mut std::io::BufWriter<std::fs::File>,
-    string: &str,
-) {
-    inputfile.write(string.as_bytes()).expect("failed");
+pub(crate) fn write_string_literal<W: Write>(writer: &mut W, string: &str) -> std::io::Result<()> {
+    writer.write_all(string.as_bytes())
 }
 
-pub(crate) fn write_slice(inputfile: &mut std::io::BufWriter<std::fs::File>, slice: &[u8]) {
-    inputfile.write(slice).expect("failed");
+pub(crate) fn write_slice<W: Write>(writer: &mut W, slice: &[u8]) -> std::io::Result<()> {
+    writer.write_all(slice)
 }
@@ -0,0 +1,571 @@
+use create_vox::{CameraMode, Color, MaterialType, VoxError, VoxFile};
+
+//chunk with no children written by hand so the layout does not depend on the crate
+fn chunk(id: &[u8; 4], content: &[u8]) -> Vec<u8> {
+    let mut bytes = id.to_vec();
+    bytes.extend_from_slice(&(content.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&[0, 0, 0, 0]);
+    bytes.extend_from_slice(content);
+    bytes
+}
+
+fn rgba(color: [u8; 4]) -> Vec<u8> {
+    chunk(b"RGBA", &color.repeat(256))
+}
+
+fn file(version: [u8; 4], children: &[Vec<u8>]) -> Vec<u8> {
+    let children = children.concat();
+    let mut bytes = b"VOX ".to_vec();
+    bytes.extend_from_slice(&version);
+    bytes.extend_from_slice(b"MAIN\x00\x00\x00\x00");
+    bytes.extend_from_slice(&(children.len() as u32).to_le_bytes());
+    bytes.extend(children);
+    bytes
+}
+
+//nodes for a single model. The translation is the frame dictionary of the model's transform.
+fn nodes(translation: &[u8]) -> Vec<Vec<u8>> {
+    let mut model_transform = vec![
+        2, 0, 0, 0, //node id
+        0, 0, 0, 0, //no attributes
+        3, 0, 0, 0, //child id
+        255, 255, 255, 255, //reserved
+        0, 0, 0, 0, //layer
+        1, 0, 0, 0, //frames
+    ];
+    model_transform.extend_from_slice(translation);
+
+    vec![
+        chunk(
+            b"nTRN",
+            &[
+                0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0, 0,
+                0, 0, 0,
+            ],
+        ),
+        chunk(b"nGRP", &[1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0]),
+        chunk(b"nTRN", &model_transform),
+        chunk(
+            b"nSHP",
+            &[3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        ),
+    ]
+}
+
+fn single_voxel_fixture() -> Vec<u8> {
+    let mut children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+    ];
+    children.extend(nodes(&[0, 0, 0, 0]));
+    children.push(rgba([75, 75, 75, 255]));
+    file([200, 0, 0, 0], &children)
+}
+
+#[test]
+fn writes_single_voxel_layout() {
+    let mut vox = VoxFile::new(2, 3, 256);
+    vox.models[0].add_voxel_at_pos(1, 2, 200, 5).unwrap();
+
+    assert_eq!(vox.to_bytes(), single_voxel_fixture());
+}
+
+#[test]
+fn reads_single_voxel_layout() {
+    let vox = VoxFile::from_bytes(&single_voxel_fixture()).unwrap();
+
+    assert_eq!(vox.version, 200);
+    assert_eq!(vox.models[0].size, (2, 3, 256));
+    assert_eq!(vox.models[0].num_of_voxels(), 1);
+    assert!(vox.models[0].is_voxel_at_pos(1, 2, 200));
+    assert_eq!(vox.get_palette_color(1), Color::new(75, 75, 75, 255));
+}
+
+#[test]
+fn writes_translation_layout() {
+    let mut vox = VoxFile::new(2, 3, 256);
+    vox.models[0].add_voxel_at_pos(1, 2, 200, 5).unwrap();
+    vox.models[0].position = Some((-1, 300, 0));
+
+    let mut translation = vec![
+        1, 0, 0, 0, //one pair
+        2, 0, 0, 0, b'_', b't', //key
+        8, 0, 0, 0, //value length
+    ];
+    translation.extend_from_slice(b"-1 300 0");
+    let mut children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+    ];
+    children.extend(nodes(&translation));
+    children.push(rgba([75, 75, 75, 255]));
+    let fixture = file([200, 0, 0, 0], &children);
+
+    assert_eq!(vox.to_bytes(), fixture);
+    let loaded = VoxFile::from_bytes(&fixture).unwrap();
+    assert_eq!(loaded.models[0].position, Some((-1, 300, 0)));
+}
+
+#[test]
+fn round_trip_is_byte_identical() {
+    let fixture = single_voxel_fixture();
+    let vox = VoxFile::from_bytes(&fixture).unwrap();
+
+    assert_eq!(vox.to_bytes(), fixture);
+}
+
+fn dict(pairs: &[(&str, &str)]) -> Vec<u8> {
+    let mut bytes = (pairs.len() as i32).to_le_bytes().to_vec();
+    for (key, value) in pairs.iter() {
+        bytes.extend_from_slice(&(key.len() as i32).to_le_bytes());
+        bytes.extend_from_slice(key.as_bytes());
+        bytes.extend_from_slice(&(value.len() as i32).to_le_bytes());
+        bytes.extend_from_slice(value.as_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn unknown_chunks_and_keys_round_trip() {
+    let mut root_transform = vec![0, 0, 0, 0];
+    root_transform.extend(dict(&[("_name", "root"), ("_note", "kept")]));
+    root_transform.extend_from_slice(&[1, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0]);
+    root_transform.extend(dict(&[]));
+
+    let mut group = vec![1, 0, 0, 0];
+    group.extend(dict(&[("_hidden", "0"), ("_group_key", "1")]));
+    group.extend_from_slice(&[1, 0, 0, 0, 2, 0, 0, 0]);
+
+    let mut model_transform = vec![2, 0, 0, 0];
+    model_transform.extend(dict(&[("_name", "tree"), ("_hidden", "1"), ("_x", "y")]));
+    model_transform.extend_from_slice(&[3, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0]);
+    model_transform.extend(dict(&[("_t", "1 2 3"), ("_f", "0")]));
+
+    let mut shape = vec![3, 0, 0, 0];
+    shape.extend(dict(&[("_shape_key", "a")]));
+    shape.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]);
+    shape.extend(dict(&[("_f", "0")]));
+
+    let mut layer = vec![0, 0, 0, 0];
+    layer.extend(dict(&[("_name", "ground"), ("_color", "255 0 0")]));
+    layer.extend_from_slice(&[255, 255, 255, 255]);
+
+    let mut material = vec![1, 0, 0, 0];
+    material.extend(dict(&[
+        ("_type", "_metal"),
+        ("_rough", "0.5"),
+        ("_plastic", "1"),
+    ]));
+
+    let children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+        chunk(b"nTRN", &root_transform),
+        chunk(b"nGRP", &group),
+        chunk(b"nTRN", &model_transform),
+        chunk(b"nSHP", &shape),
+        chunk(b"LAYR", &layer),
+        rgba([75, 75, 75, 255]),
+        chunk(b"MATL", &material),
+        chunk(b"XTRA", &[1, 2, 3, 4, 5]),
+    ];
+    let fixture = file([200, 0, 0, 0], &children);
+
+    let vox = VoxFile::from_bytes(&fixture).unwrap();
+    assert_eq!(vox.models[0].name, Some(String::from("tree")));
+    assert_eq!(vox.models[0].position, Some((1, 2, 3)));
+    assert_eq!(vox.to_bytes(), fixture);
+}
+
+#[test]
+fn invalid_rotation_is_an_error() {
+    let mut rotation = vec![1, 0, 0, 0, 2, 0, 0, 0, b'_', b'r', 1, 0, 0, 0];
+    rotation.extend_from_slice(b"0");
+    let mut children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+    ];
+    children.extend(nodes(&rotation));
+    children.push(rgba([75, 75, 75, 255]));
+
+    match VoxFile::from_bytes(&file([200, 0, 0, 0], &children)) {
+        Err(VoxError::InvalidDict { .. }) => {}
+        _ => panic!("expected an invalid dictionary error"),
+    }
+}
+
+#[test]
+fn animation_frames_round_trip() {
+    let mut root_transform = vec![0, 0, 0, 0];
+    root_transform.extend(dict(&[]));
+    root_transform.extend_from_slice(&[1, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0]);
+    root_transform.extend(dict(&[]));
+
+    let mut group = vec![1, 0, 0, 0];
+    group.extend(dict(&[]));
+    group.extend_from_slice(&[1, 0, 0, 0, 2, 0, 0, 0]);
+
+    let mut model_transform = vec![2, 0, 0, 0];
+    model_transform.extend(dict(&[]));
+    model_transform.extend_from_slice(&[3, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 3, 0, 0, 0]);
+    model_transform.extend(dict(&[("_t", "0 0 0"), ("_f", "0")]));
+    model_transform.extend(dict(&[("_t", "0 0 5"), ("_f", "10")]));
+    model_transform.extend(dict(&[("_r", "17"), ("_f", "20"), ("_ease", "1")]));
+
+    let mut shape = vec![3, 0, 0, 0];
+    shape.extend(dict(&[]));
+    shape.extend_from_slice(&[2, 0, 0, 0, 0, 0, 0, 0]);
+    shape.extend(dict(&[("_f", "0")]));
+    shape.extend_from_slice(&[1, 0, 0, 0]);
+    shape.extend(dict(&[("_f", "15")]));
+
+    let children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+        chunk(b"SIZE", &[1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0]),
+        chunk(b"XYZI", &[0, 0, 0, 0]),
+        chunk(b"nTRN", &root_transform),
+        chunk(b"nGRP", &group),
+        chunk(b"nTRN", &model_transform),
+        chunk(b"nSHP", &shape),
+        rgba([75, 75, 75, 255]),
+    ];
+    let fixture = file([200, 0, 0, 0], &children);
+
+    let mut vox = VoxFile::from_bytes(&fixture).unwrap();
+    let model = &vox.models[0];
+    assert_eq!(model.position, Some((0, 0, 0)));
+    assert_eq!(model.frames.len(), 2);
+    assert_eq!(model.frames[0].0, 10);
+    assert_eq!(model.frames[0].1.translation, Some((0, 0, 5)));
+    assert_eq!(model.frames[1].0, 20);
+    assert_eq!(model.frames[1].1.rotation.unwrap().to_byte(), 17);
+    assert_eq!(model.model_frames, vec![(15, 1)]);
+
+    //the frames are written back in the same layout
+    let bytes = vox.to_bytes();
+    let start = fixture.len() - 1024 - 12 - model_transform.len() - 12 - shape.len() - 12;
+    let frames = &fixture[start..fixture.len() - 1024 - 12];
+    assert!(bytes.windows(frames.len()).any(|window| window == frames));
+
+    //unknown attributes stay with the frame index they were on when a frame is removed
+    vox.models[0].frames.remove(0);
+    let bytes = vox.to_bytes();
+    let last_frame = dict(&[("_r", "17"), ("_f", "20"), ("_ease", "1")]);
+    assert!(bytes
+        .windows(last_frame.len())
+        .any(|window| window == last_frame.as_slice()));
+}
+
+#[test]
+fn empty_nodes_round_trip() {
+    let mut root_transform = vec![0, 0, 0, 0];
+    root_transform.extend(dict(&[]));
+    root_transform.extend_from_slice(&[1, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0]);
+    root_transform.extend(dict(&[]));
+
+    let group = [
+        1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, //two children
+        2, 0, 0, 0, 4, 0, 0, 0,
+    ];
+
+    //a transform with no frames
+    let mut model_transform = vec![2, 0, 0, 0];
+    model_transform.extend(dict(&[]));
+    model_transform.extend_from_slice(&[3, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let shape = [3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    //a shape with no models
+    let mut empty_transform = vec![4, 0, 0, 0];
+    empty_transform.extend(dict(&[("_name", "empty")]));
+    empty_transform.extend_from_slice(&[5, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0]);
+    empty_transform.extend(dict(&[("_t", "5 5 5")]));
+    let empty_shape = [5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    let children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+        chunk(b"nTRN", &root_transform),
+        chunk(b"nGRP", &group),
+        chunk(b"nTRN", &model_transform),
+        chunk(b"nSHP", &shape),
+        chunk(b"nTRN", &empty_transform),
+        chunk(b"nSHP", &empty_shape),
+        rgba([75, 75, 75, 255]),
+    ];
+    let fixture = file([200, 0, 0, 0], &children);
+
+    let mut vox = VoxFile::from_bytes(&fixture).unwrap();
+    assert_eq!(vox.models[0].position, None);
+    assert_eq!(vox.to_bytes(), fixture);
+
+    //the transform gets a frame once the model is moved
+    vox.models[0].position = Some((1, 2, 3));
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.models[0].position, Some((1, 2, 3)));
+}
+
+#[test]
+fn material_ids_from_magicavoxel() {
+    //MagicaVoxel writes a material for every index with 256 used for index 0
+    let mut first = vec![1, 0, 0, 0];
+    first.extend(dict(&[("_type", "_plastic"), ("_weight", "1")]));
+    let mut last = 256i32.to_le_bytes().to_vec();
+    last.extend(dict(&[("_type", "_media"), ("_media", "_fog")]));
+
+    let mut children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+    ];
+    children.extend(nodes(&[0, 0, 0, 0]));
+    children.push(rgba([75, 75, 75, 255]));
+    children.push(chunk(b"MATL", &first));
+    children.push(chunk(b"MATL", &last));
+    let fixture = file([200, 0, 0, 0], &children);
+
+    let vox = VoxFile::from_bytes(&fixture).unwrap();
+    let material = vox.material(1).unwrap();
+    assert_eq!(
+        material.material_type,
+        MaterialType::Other(String::from("_plastic"))
+    );
+    assert_eq!(material.weight, Some(1.0));
+    //both materials are written back unchanged
+    let bytes = vox.to_bytes();
+    for material in [chunk(b"MATL", &first), chunk(b"MATL", &last)].iter() {
+        assert!(bytes
+            .windows(material.len())
+            .any(|window| window == &material[..]));
+    }
+
+    let mut too_large = 257i32.to_le_bytes().to_vec();
+    too_large.extend(dict(&[]));
+    children.push(chunk(b"MATL", &too_large));
+    match VoxFile::from_bytes(&file([200, 0, 0, 0], &children)) {
+        Err(VoxError::InvalidChunk { .. }) => {}
+        _ => panic!("expected an invalid chunk error"),
+    }
+}
+
+#[test]
+fn magicavoxel_chunk_order_round_trip() {
+    let mut first_material = vec![1, 0, 0, 0];
+    first_material.extend(dict(&[
+        ("_type", "_diffuse"),
+        ("_weight", "1"),
+        ("_rough", "0.1"),
+        ("_spec", "0.5"),
+        ("_ior", "0.3"),
+    ]));
+    //keys in a different order and numbers written differently than this crate would
+    let mut last_material = 256i32.to_le_bytes().to_vec();
+    last_material.extend(dict(&[
+        ("_metal", "1.0"),
+        ("_type", "_metal"),
+        ("_rough", "0.100"),
+        ("_weight", "1"),
+    ]));
+    let bloom = dict(&[("_type", "_bloom"), ("_mix", "0.5")]);
+    let sun = dict(&[("_i", "0.7"), ("_type", "_inf"), ("_angle", "50 50")]);
+    let mut camera = vec![0, 0, 0, 0];
+    camera.extend(dict(&[
+        ("_mode", "pers"),
+        ("_focus", "0 0 0"),
+        ("_angle", "30.0 -45 0"),
+        ("_radius", "40"),
+        ("_frustum", "0.414214"),
+        ("_fov", "45"),
+    ]));
+    let mut notes = vec![1, 0, 0, 0];
+    notes.extend_from_slice(&[4, 0, 0, 0]);
+    notes.extend_from_slice(b"skin");
+    let index_map: Vec<u8> = (0..=255).rev().collect();
+
+    //chunks in the order MagicaVoxel writes them
+    let mut children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+    ];
+    children.extend(nodes(&[0, 0, 0, 0]));
+    children.extend(vec![
+        rgba([75, 75, 75, 255]),
+        chunk(b"IMAP", &index_map),
+        chunk(b"MATL", &first_material),
+        chunk(b"MATL", &last_material),
+        chunk(b"rOBJ", &bloom),
+        chunk(b"rOBJ", &sun),
+        chunk(b"rCAM", &camera),
+        chunk(b"NOTE", &notes),
+    ]);
+    let fixture = file([200, 0, 0, 0], &children);
+
+    let mut vox = VoxFile::from_bytes(&fixture).unwrap();
+    assert_eq!(vox.render_objects[1].object_type, "_inf");
+    assert_eq!(vox.to_bytes(), fixture);
+
+    //only the changed value is written differently
+    vox.cameras[0].fov = Some(50.0);
+    let mut changed = vec![0, 0, 0, 0];
+    changed.extend(dict(&[
+        ("_mode", "pers"),
+        ("_focus", "0 0 0"),
+        ("_angle", "30.0 -45 0"),
+        ("_radius", "40"),
+        ("_frustum", "0.414214"),
+        ("_fov", "50"),
+    ]));
+    let changed = chunk(b"rCAM", &changed);
+    let bytes = vox.to_bytes();
+    assert!(bytes
+        .windows(changed.len())
+        .any(|window| window == &changed[..]));
+}
+
+//a transform with the child given and a group with the children given
+fn group_level(id: i32, children: &[i32]) -> Vec<Vec<u8>> {
+    let mut transform = id.to_le_bytes().to_vec();
+    transform.extend(dict(&[]));
+    transform.extend_from_slice(&(id + 1).to_le_bytes());
+    transform.extend_from_slice(&[255, 255, 255, 255, 255, 255, 255, 255, 1, 0, 0, 0]);
+    transform.extend(dict(&[]));
+
+    let mut group = (id + 1).to_le_bytes().to_vec();
+    group.extend(dict(&[]));
+    group.extend_from_slice(&(children.len() as i32).to_le_bytes());
+    for child in children {
+        group.extend_from_slice(&child.to_le_bytes());
+    }
+    vec![chunk(b"nTRN", &transform), chunk(b"nGRP", &group)]
+}
+
+#[test]
+fn nodes_used_twice_are_invalid() {
+    //each group has the next group as a child twice so the tree would double at every level
+    let mut children = vec![rgba([75, 75, 75, 255])];
+    for level in 0..40 {
+        let next = level * 2 + 2;
+        children.extend(group_level(level * 2, &[next, next]));
+    }
+    children.extend(group_level(80, &[]));
+    match VoxFile::from_bytes(&file([200, 0, 0, 0], &children)) {
+        Err(VoxError::InvalidNode { .. }) => {}
+        _ => panic!("expected invalid node error"),
+    }
+
+    //groups inside groups are fine when each is only used once
+    let mut children = vec![rgba([75, 75, 75, 255])];
+    for level in 0..40 {
+        children.extend(group_level(level * 2, &[level * 2 + 2]));
+    }
+    children.extend(group_level(80, &[]));
+    let fixture = file([200, 0, 0, 0], &children);
+    let vox = VoxFile::from_bytes(&fixture).unwrap();
+    assert_eq!(vox.groups().len(), 40);
+}
+
+#[test]
+fn deep_files_are_errors() {
+    //a chain of transforms that each have the next transform as their child
+    let mut children = vec![rgba([75, 75, 75, 255])];
+    for id in 0..300_000i32 {
+        let mut transform = id.to_le_bytes().to_vec();
+        transform.extend(dict(&[]));
+        transform.extend_from_slice(&(id + 1).to_le_bytes());
+        transform.extend_from_slice(&[255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 0, 0]);
+        children.push(chunk(b"nTRN", &transform));
+    }
+    match VoxFile::from_bytes(&file([200, 0, 0, 0], &children)) {
+        Err(VoxError::InvalidNode { .. }) => {}
+        _ => panic!("expected invalid node error"),
+    }
+
+    //chunks that each have the next chunk as their child
+    let depth = 200_000;
+    let mut chunks = Vec::with_capacity(depth * 12);
+    for level in 0..depth {
+        chunks.extend_from_slice(b"DEEP");
+        chunks.extend_from_slice(&[0, 0, 0, 0]);
+        chunks.extend_from_slice(&(((depth - level - 1) * 12) as u32).to_le_bytes());
+    }
+    match VoxFile::from_bytes(&file([200, 0, 0, 0], &[chunks])) {
+        Err(VoxError::InvalidChunk { .. }) => {}
+        _ => panic!("expected invalid chunk error"),
+    }
+}
+
+#[test]
+fn unknown_camera_values_round_trip() {
+    let mut camera = vec![0, 0, 0, 0];
+    camera.extend(dict(&[
+        ("_mode", "zzzz"),
+        ("_focus", "0 0 1.0"),
+        ("_radius", "far"),
+        ("_fov", "45"),
+    ]));
+    let mut children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+    ];
+    children.extend(nodes(&[0, 0, 0, 0]));
+    children.push(rgba([75, 75, 75, 255]));
+    children.push(chunk(b"rCAM", &camera));
+    let fixture = file([200, 0, 0, 0], &children);
+
+    let mut vox = VoxFile::from_bytes(&fixture).unwrap();
+    assert_eq!(
+        vox.cameras[0].mode,
+        Some(CameraMode::Other(String::from("zzzz")))
+    );
+    assert_eq!(vox.cameras[0].focus, Some((0.0, 0.0, 1.0)));
+    assert_eq!(vox.cameras[0].radius, None);
+    assert_eq!(vox.cameras[0].fov, Some(45.0));
+    assert_eq!(vox.to_bytes(), fixture);
+
+    //setting the value replaces the one that could not be read
+    vox.cameras[0].radius = Some(20.0);
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.cameras[0].radius, Some(20.0));
+    assert_eq!(loaded.cameras[0].mode, vox.cameras[0].mode);
+}
+
+#[test]
+fn removing_a_layer_changes_empty_nodes() {
+    let mut root_transform = vec![0, 0, 0, 0];
+    root_transform.extend(dict(&[]));
+    root_transform.extend_from_slice(&[1, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0]);
+    root_transform.extend(dict(&[]));
+    let group = [1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0];
+
+    //a shape with no models on layer 1
+    let empty_transform = |layer: u8| {
+        let mut transform = vec![2, 0, 0, 0];
+        transform.extend(dict(&[]));
+        transform.extend_from_slice(&[3, 0, 0, 0, 255, 255, 255, 255, layer, 0, 0, 0, 1, 0, 0, 0]);
+        transform.extend(dict(&[]));
+        transform
+    };
+    let empty_shape = [3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    let mut layer = vec![1, 0, 0, 0];
+    layer.extend(dict(&[("_name", "trees")]));
+    layer.extend_from_slice(&[255, 255, 255, 255]);
+
+    let children = vec![
+        chunk(b"SIZE", &[2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0, 0]),
+        chunk(b"XYZI", &[1, 0, 0, 0, 1, 2, 200, 5]),
+        chunk(b"nTRN", &root_transform),
+        chunk(b"nGRP", &group),
+        chunk(b"nTRN", &empty_transform(1)),
+        chunk(b"nSHP", &empty_shape),
+        chunk(b"LAYR", &layer),
+        rgba([75, 75, 75, 255]),
+    ];
+    let mut vox = VoxFile::from_bytes(&file([200, 0, 0, 0], &children)).unwrap();
+    vox.remove_layer(1).unwrap();
+
+    let moved = chunk(b"nTRN", &empty_transform(0));
+    let bytes = vox.to_bytes();
+    assert!(bytes
+        .windows(moved.len())
+        .any(|window| window == moved.as_slice()));
+}
@@ -1,4 +1,8 @@
-use create_vox::{Color, VoxFile, Voxel};
+use create_vox::{
+    sdf, Axis, Camera, CameraMode, Chunk, ChunkReader, Color, FillMode, Keyframe, Material,
+    MaterialType, MediaType, Mesh, Model, Overflow, PlaceMode, RenderObject, Rotation, Storage,
+    Transform, Triangle, VoxError, VoxFile, Voxel,
+};
 #[test]
 //#[ignore]
 fn it_works() {
@@ -8,7 +12,7 @@ fn it_works() {
     vox.add_model_copy(0, 10, 10, 10);
     vox.add_layer(String::from("cool layer"), false);
     vox.models[0].layer = Some(1);
-    vox.save("tester.vox");
+    vox.save("tester.vox").unwrap();
 }
 
 #[test]
@@ -18,9 +22,10 @@ fn size_too_big() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn voxel_at_pos() {
     let mut test_vox = VoxFile::new(10, 10, 10);
-    test_vox.models[0].add_voxel_at_pos(3, 4, 2, 1);
+    test_vox.models[0].add_voxel_at_pos(3, 4, 2, 1).unwrap();
     assert_eq!(true, test_vox.models[0].is_voxel_at_pos(3, 4, 2));
 }
 #[test]
@@ -28,3 +33,1113 @@ fn adding_cube(){
     let mut test_vox = VoxFile::new(50, 1, 15);
     test_vox.models[0].add_cube(0,0,0, 40, 1,15,1).expect("Failed to add cube");
 }
+
+#[test]
+fn loading_bad_files() {
+    let path = std::env::temp_dir().join("create_vox_bad_magic.vox");
+    std::fs::write(&path, b"NOT A VOX FILE").unwrap();
+    match VoxFile::load(path.to_str().unwrap()) {
+        Err(VoxError::BadMagic { found }) => assert_eq!(&found, b"NOT "),
+        _ => panic!("expected bad magic error"),
+    }
+
+    let path = std::env::temp_dir().join("create_vox_truncated.vox");
+    std::fs::write(
+        &path,
+        b"VOX \x96\x00\x00\x00MAIN\x00\x00\x00\x00\x10\x00\x00\x00SIZE\x0c",
+    )
+    .unwrap();
+    match VoxFile::load(path.to_str().unwrap()) {
+        Err(VoxError::UnexpectedEof { offset }) => assert_eq!(offset, 24),
+        _ => panic!("expected unexpected eof error"),
+    }
+}
+
+#[test]
+fn round_trip_in_memory() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 2).unwrap();
+    vox.set_palette_color(2, 255, 100, 0, 255);
+    vox.add_model_copy(0, 10, 10, 10);
+
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.models.len(), 1);
+    assert_eq!(loaded.models[0].num_of_voxels(), 125);
+    assert_eq!(loaded.get_palette_color(2), Color::new(255, 100, 0, 255));
+
+    //a shared reference is enough to write the file
+    let shared: &VoxFile = &loaded;
+    let mut bytes = Vec::new();
+    shared.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes, shared.to_bytes());
+}
+
+#[test]
+fn shape_of_missing_model_is_kept() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.add_model_copy(7, 10, 10, 10);
+
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.copies().len(), 1);
+    assert_eq!(loaded.copies()[0].model_id, 7);
+    assert_eq!(loaded.copies()[0].position, Some((10, 10, 10)));
+    assert_eq!(loaded.to_bytes(), vox.to_bytes());
+}
+
+#[test]
+fn chunk_tree() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.add_model(Model::new(5, 5, 5));
+    vox.add_layer(String::from("layer"), true);
+    let bytes = vox.to_bytes();
+
+    let chunks = ChunkReader::new(&bytes[8..])
+        .collect::<Result<Vec<Chunk>, VoxError>>()
+        .unwrap();
+    assert_eq!(chunks.len(), 1);
+    let ids: Vec<&[u8; 4]> = chunks[0].children.iter().map(|chunk| &chunk.id).collect();
+    assert_eq!(
+        ids,
+        vec![
+            b"SIZE", b"XYZI", b"SIZE", b"XYZI", b"nTRN", b"nGRP", b"nTRN", b"nSHP", b"nTRN",
+            b"nSHP", b"LAYR", b"RGBA"
+        ]
+    );
+}
+
+#[test]
+fn file_version() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    assert_eq!(vox.version, 200);
+    let bytes = vox.to_bytes();
+    assert_eq!(&bytes[0..8], b"VOX \xc8\x00\x00\x00");
+
+    vox.version = 150;
+    assert_eq!(VoxFile::from_bytes(&vox.to_bytes()).unwrap().version, 150);
+
+    vox.version = 300;
+    match VoxFile::from_bytes(&vox.to_bytes()) {
+        Err(VoxError::UnsupportedVersion(version)) => assert_eq!(version, 300),
+        _ => panic!("expected unsupported version error"),
+    }
+}
+
+#[test]
+fn materials_round_trip() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    let mut glass = Material::new(MaterialType::Glass);
+    glass.roughness = Some(0.1);
+    glass.ior = Some(0.3);
+    glass.transparency = Some(0.5);
+    vox.set_material(3, glass.clone());
+    let mut cloud = Material::new(MaterialType::Media);
+    cloud.density = Some(0.05);
+    cloud.media = Some(MediaType::Scatter);
+    vox.set_material(200, cloud.clone());
+
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.material(3), Some(&glass));
+    assert_eq!(loaded.material(200), Some(&cloud));
+    assert_eq!(loaded.material(4), None);
+}
+
+#[test]
+fn cameras_and_render_objects_round_trip() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    let mut camera = Camera::new(0);
+    camera.mode = Some(CameraMode::Orthographic);
+    camera.focus = Some((5.0, 5.0, 0.5));
+    camera.angle = Some((30.0, -45.0, 0.0));
+    camera.radius = Some(24.0);
+    camera.frustum = Some(0.414214);
+    camera.fov = Some(45.0);
+    vox.cameras.push(camera.clone());
+    let mut fog = RenderObject::new("_fog_uni");
+    fog.set_property("_color", "255 255 255");
+    fog.set_property("_d", 0.25);
+    vox.render_objects.push(fog.clone());
+
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.cameras, vec![camera]);
+    assert_eq!(loaded.render_objects, vec![fog]);
+    assert_eq!(loaded.render_objects[0].property("_d"), Some("0.25"));
+}
+
+#[test]
+fn palette_notes_and_index_map() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.set_palette_note(0, "reds");
+    vox.set_palette_note(1, "greens");
+    let mut index_map = [0; 256];
+    for (i, index) in index_map.iter_mut().enumerate() {
+        *index = (255 - i) as u8;
+    }
+    vox.index_map = Some(index_map);
+
+    let mut loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.palette_notes, vec!["reds", "greens"]);
+    assert_eq!(loaded.index_map.map(|map| map[0]), Some(255));
+
+    loaded.swap_palette_colors(255, 3);
+    let index_map = loaded.index_map.unwrap();
+    assert_eq!(index_map[0], 3);
+    assert_eq!(index_map[252], 255);
+    assert_eq!(index_map[1], 254);
+}
+
+#[test]
+fn rotations() {
+    let valid: Vec<Rotation> = (0..=255).filter_map(Rotation::from_byte).collect();
+    assert_eq!(valid.len(), 48);
+    for rotation in valid.iter() {
+        assert_eq!(Rotation::from_matrix(rotation.to_matrix()), Some(*rotation));
+        assert_eq!(rotation.compose(rotation.inverse()), Rotation::IDENTITY);
+    }
+    assert_eq!(Rotation::from_euler_90s(1, 0, 0), Rotation::rotate_x90());
+
+    let mut vox = VoxFile::new(10, 10, 10);
+    let rotation = Rotation::from_euler_90s(1, 2, 0);
+    vox.models[0].rotation = Some(rotation);
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.models[0].rotation, Some(rotation));
+}
+
+#[test]
+fn copies_with_transforms() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    let layer = vox.add_layer(String::from("props"), false);
+    for i in 0..10 {
+        vox.add_model_copy_with(
+            0,
+            Transform {
+                translation: Some((i * 10, 0, 0)),
+                rotation: Some(Rotation::from_euler_90s(0, 0, i)),
+                layer: Some(layer),
+                name: Some(format!("fence {}", i)),
+                hidden: Some(i % 2 == 0),
+            },
+        );
+    }
+
+    let mut loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.copies().len(), 10);
+    let copy = loaded.find_copy("fence 3").unwrap();
+    assert_eq!(copy.position, Some((30, 0, 0)));
+    assert_eq!(copy.rotation, Some(Rotation::from_euler_90s(0, 0, 3)));
+    assert_eq!(copy.layer, Some(layer));
+    assert_eq!(copy.hidden, Some(false));
+
+    loaded.retain_copies(|copy| copy.hidden != Some(true));
+    assert_eq!(loaded.copies_of_model(0).count(), 5);
+}
+
+#[test]
+fn nested_groups_round_trip() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    let town = vox.add_group(
+        None,
+        Transform {
+            name: Some(String::from("town")),
+            ..Transform::default()
+        },
+    );
+    let house = vox.add_group(
+        Some(town),
+        Transform {
+            translation: Some((20, 0, 0)),
+            name: Some(String::from("house")),
+            hidden: Some(true),
+            ..Transform::default()
+        },
+    );
+    vox.set_model_group(0, Some(town)).unwrap();
+    let copy = vox.add_model_copy_with(0, Transform::default());
+    vox.set_copy_group(copy, Some(house)).unwrap();
+
+    let bytes = vox.to_bytes();
+    let mut loaded = VoxFile::from_bytes(&bytes).unwrap();
+    let loaded_town = loaded.find_group("town").unwrap();
+    let loaded_house = loaded.find_group("house").unwrap();
+    assert_eq!(loaded_town.parent(), None);
+    assert_eq!(loaded_house.parent(), Some(loaded_town.get_id()));
+    assert_eq!(loaded_house.position, Some((20, 0, 0)));
+    assert_eq!(loaded_house.hidden, Some(true));
+    assert_eq!(loaded.models[0].group(), Some(loaded_town.get_id()));
+    assert_eq!(loaded.copies()[0].group(), Some(loaded_house.get_id()));
+    let house_id = loaded_house.get_id();
+    assert_eq!(loaded.to_bytes(), bytes);
+
+    loaded.move_group(house_id, None).unwrap();
+    let moved = VoxFile::from_bytes(&loaded.to_bytes()).unwrap();
+    assert_eq!(moved.find_group("house").unwrap().parent(), None);
+}
+
+#[test]
+fn animation_frames() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.add_model(Model::new(5, 5, 5));
+    vox.models[0].position = Some((0, 0, 0));
+    vox.models[0].frames.push((
+        10,
+        Keyframe {
+            translation: Some((0, 0, 20)),
+            ..Keyframe::default()
+        },
+    ));
+    vox.models[0].model_frames.push((5, 1));
+
+    vox.add_model_copy(1, 30, 0, 0);
+    vox.copies_mut()[0].frames.push((
+        8,
+        Keyframe {
+            rotation: Some(Rotation::rotate_z90()),
+            ..Keyframe::default()
+        },
+    ));
+    let group = vox.add_group(None, Transform::default());
+    vox.group_mut(group).unwrap().frames.push((
+        3,
+        Keyframe {
+            translation: Some((1, 2, 3)),
+            ..Keyframe::default()
+        },
+    ));
+
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.models[0].frames, vox.models[0].frames);
+    assert_eq!(loaded.models[0].model_frames, vec![(5, 1)]);
+    assert_eq!(loaded.copies()[0].frames, vox.copies()[0].frames);
+    assert_eq!(loaded.groups()[0].frames, vox.groups()[0].frames);
+}
+
+#[test]
+fn layers() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.add_model(Model::new(5, 5, 5));
+    let ground = vox.add_layer(String::from("ground"), false);
+    let trees = vox.add_layer(String::from("trees"), false);
+    let sky = vox.add_layer(String::from("sky"), true);
+    assert_eq!((ground, trees, sky), (0, 1, 2));
+    vox.layer_mut(trees).unwrap().color = Some(Color::new(0, 200, 0, 255));
+    vox.models[1].layer = Some(trees);
+    vox.add_model_copy_with(
+        0,
+        Transform {
+            layer: Some(sky),
+            ..Transform::default()
+        },
+    );
+
+    let mut loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.layers().len(), 3);
+    let layer = loaded.find_layer("trees").unwrap();
+    assert_eq!(layer.get_id(), trees);
+    assert_eq!(layer.color, Some(Color::new(0, 200, 0, 255)));
+    assert_eq!(loaded.layer(sky).unwrap().hidden, Some(true));
+    assert_eq!(loaded.models_in_layer(trees).count(), 1);
+
+    loaded.swap_layers(trees, sky).unwrap();
+    assert_eq!(loaded.find_layer("trees").unwrap().get_id(), sky);
+    assert_eq!(loaded.models_in_layer(sky).count(), 1);
+    assert_eq!(loaded.copies()[0].layer, Some(trees));
+    assert!(loaded.swap_layers(trees, 10).is_err());
+
+    let removed = loaded.remove_layer(sky).unwrap();
+    assert_eq!(removed.name, Some(String::from("trees")));
+    assert_eq!(loaded.models_in_layer(0).count(), 2);
+    assert!(loaded.remove_layer(sky).is_none());
+
+    loaded.remove_layer(trees).unwrap();
+    assert_eq!(loaded.copies()[0].layer, Some(0));
+    loaded.remove_layer(ground).unwrap();
+    assert_eq!(loaded.models_in_layer(0).count(), 2);
+}
+
+#[test]
+fn voxel_storage() {
+    for storage in [Storage::Hashed, Storage::Dense].iter() {
+        let mut model = Model::new(256, 10, 10);
+        model.set_storage(*storage);
+        model.add_cube(0, 0, 0, 5, 5, 5, 1).unwrap();
+        model.add_cube(0, 0, 0, 5, 5, 5, 2).unwrap();
+        assert_eq!(model.num_of_voxels(), 125);
+
+        model.add_voxel_at_pos(255, 9, 9, 3).unwrap();
+        assert!(model.is_voxel_at_pos(255, 9, 9));
+        assert!(model.add_voxel_at_pos(255, 10, 9, 3).is_err());
+
+        model.retain_voxels(|voxel| voxel.colorindex == 3);
+        assert_eq!(model.num_of_voxels(), 1);
+        assert!(!model.is_voxel_at_pos(0, 0, 0));
+
+        model.add_voxel_at_pos(1, 1, 1, 4).unwrap();
+        model.change_voxels(|voxel| voxel.position = (1, 1, 1));
+        assert_eq!(model.num_of_voxels(), 1);
+
+        model.set_size(1, 1, 1);
+        assert_eq!(model.num_of_voxels(), 0);
+    }
+
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].set_storage(Storage::Dense);
+    vox.models[0].add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert!(loaded.models[0].is_voxel_at_pos(1, 2, 3));
+}
+
+#[test]
+fn voxel_accessors() {
+    let mut vox = VoxFile::new(20, 20, 20);
+    let model = &mut vox.models[0];
+    model.add_cube(0, 0, 0, 4, 4, 4, 1).unwrap();
+    model.set(19, 19, 19, 7).unwrap();
+    assert_eq!(model.get(19, 19, 19), Some(7));
+    assert_eq!(model.remove(0, 0, 0), Some(1));
+    assert_eq!(model.get(0, 0, 0), None);
+    assert_eq!(model.voxels().count(), 64);
+
+    for (_, colorindex) in model.voxels_mut().iter_mut() {
+        *colorindex += 1;
+    }
+    assert_eq!(model.get(1, 1, 1), Some(2));
+    assert_eq!(model.iter_in_region((0, 0, 0), (1, 1, 1)).count(), 7);
+    assert_eq!(model.iter_in_region((10, 10, 10), (19, 19, 19)).count(), 1);
+
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    let mut total = 0;
+    for voxel in &loaded.models[0] {
+        total += voxel.colorindex as i32;
+    }
+    assert_eq!(total, 63 * 2 + 8);
+    let positions: Vec<(u8, u8, u8)> = loaded.models[0]
+        .clone()
+        .into_iter()
+        .map(|voxel| voxel.position)
+        .collect();
+    assert!(positions.contains(&(19, 19, 19)));
+
+    //voxels set to 0 are removed
+    for (position, colorindex) in vox.models[0].voxels_mut().iter_mut() {
+        if position.0 == 0 {
+            *colorindex = 0;
+        }
+    }
+    assert_eq!(vox.models[0].get(0, 1, 1), None);
+    assert_eq!(vox.models[0].num_of_voxels(), 64 - 15);
+    assert!(vox.models[0].voxels().all(|voxel| voxel.colorindex != 0));
+    vox.models[0].change_voxels(|voxel| {
+        if voxel.position.1 == 1 {
+            voxel.colorindex = 0;
+        }
+    });
+    assert_eq!(vox.models[0].num_of_voxels(), 64 - 15 - 12);
+    let loaded = VoxFile::from_bytes(&vox.to_bytes()).unwrap();
+    assert_eq!(loaded.models[0].voxels().count(), 64 - 15 - 12);
+    assert_eq!(loaded.models[0].get(0, 1, 1), None);
+}
+
+#[test]
+fn place_modes() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_cube(0, 0, 0, 2, 2, 2, 1).unwrap();
+
+    model.add_cube(0, 0, 0, 3, 3, 3, 2).unwrap();
+    assert_eq!(model.get(0, 0, 0), Some(2));
+
+    model.place_mode = PlaceMode::KeepExisting;
+    model.add_voxel_at_pos(0, 0, 0, 3).unwrap();
+    assert_eq!(model.get(0, 0, 0), Some(2));
+
+    model.place_mode = PlaceMode::Error;
+    assert!(model.add_voxel(Voxel::new(1, 1, 1, 4)).is_err());
+    assert!(model.add_cube(2, 2, 2, 5, 5, 5, 4).is_err());
+    assert_eq!(model.get(4, 4, 4), None);
+    model.add_cube(3, 3, 3, 5, 5, 5, 4).unwrap();
+    assert_eq!(model.get(4, 4, 4), Some(4));
+
+    model.place_mode = PlaceMode::merge(|existing, new| if existing == new { 0 } else { new });
+    model.add_voxel_at_pos(4, 4, 4, 4).unwrap();
+    assert_eq!(model.get(4, 4, 4), None);
+    model.add_voxel_at_pos(3, 3, 3, 5).unwrap();
+    assert_eq!(model.get(3, 3, 3), Some(5));
+
+    model.set(3, 3, 3, 6).unwrap();
+    assert_eq!(model.get(3, 3, 3), Some(6));
+}
+
+#[test]
+fn shapes() {
+    let mut model = Model::new(40, 40, 40);
+    model.add_sphere((20.0, 20.0, 20.0), 0.0, 1).unwrap();
+    assert_eq!(model.num_of_voxels(), 1);
+    model.clear_voxels();
+
+    model.add_sphere((20.0, 20.0, 20.0), 10.0, 1).unwrap();
+    let solid = model.num_of_voxels();
+    model.clear_voxels();
+    model
+        .add_hollow_sphere((20.0, 20.0, 20.0), 10.0, 2.0, 1)
+        .unwrap();
+    assert!(model.num_of_voxels() < solid);
+    assert!(!model.is_voxel_at_pos(20, 20, 20));
+    assert!(model.is_voxel_at_pos(20, 20, 30));
+    model.clear_voxels();
+
+    //a cylinder along each axis has the same number of voxels
+    let along_x = {
+        model
+            .add_cylinder((5.0, 20.0, 20.0), (35.0, 20.0, 20.0), 4.0, 1)
+            .unwrap();
+        model.num_of_voxels()
+    };
+    model.clear_voxels();
+    model
+        .add_cylinder((20.0, 20.0, 5.0), (20.0, 20.0, 35.0), 4.0, 1)
+        .unwrap();
+    assert_eq!(model.num_of_voxels(), along_x);
+    model.clear_voxels();
+
+    model
+        .add_hollow_cylinder((20.0, 20.0, 5.0), (20.0, 20.0, 35.0), 4.0, 1.0, 1)
+        .unwrap();
+    assert!(!model.is_voxel_at_pos(20, 20, 20));
+    assert!(model.is_voxel_at_pos(20, 20, 5));
+    model.clear_voxels();
+
+    model
+        .add_cone((20.0, 20.0, 0.0), (20.0, 20.0, 30.0), 10.0, 1)
+        .unwrap();
+    assert!(model.is_voxel_at_pos(30, 20, 0));
+    assert!(model.is_voxel_at_pos(20, 20, 30));
+    assert!(!model.is_voxel_at_pos(30, 20, 15));
+    model.clear_voxels();
+
+    model
+        .add_hollow_torus((20.0, 20.0, 20.0), 12.0, 5.0, 2.0, 1)
+        .unwrap();
+    assert!(model.is_voxel_at_pos(37, 20, 20));
+    assert!(!model.is_voxel_at_pos(32, 20, 20));
+    model.clear_voxels();
+
+    model
+        .add_ellipsoid((20.0, 20.0, 20.0), (19.0, 5.0, 2.0), 1)
+        .unwrap();
+    assert!(model.is_voxel_at_pos(1, 20, 20));
+    assert!(!model.is_voxel_at_pos(20, 20, 23));
+
+    //nothing is added when a shape is clipped
+    let count = model.num_of_voxels();
+    assert!(model.add_sphere((38.0, 20.0, 20.0), 5.0, 2).is_err());
+    assert!(model.add_torus((20.0, 20.0, 2.0), 10.0, 3.0, 2).is_err());
+    assert!(model
+        .add_cone((20.0, 20.0, 0.0), (20.0, 20.0, 45.0), 5.0, 2)
+        .is_err());
+    assert!(model
+        .add_cylinder((1.0, 1.0, 1.0), (1.0, 1.0, 1.0), 1.0, 2)
+        .is_err());
+    assert_eq!(model.num_of_voxels(), count);
+
+    model.place_mode = PlaceMode::Error;
+    assert!(model.add_sphere((20.0, 20.0, 20.0), 3.0, 2).is_err());
+}
+
+#[test]
+fn lines_and_curves() {
+    let mut model = Model::new(30, 30, 30);
+    model.add_line((0, 0, 0), (29, 29, 29), 1, 1).unwrap();
+    assert_eq!(model.num_of_voxels(), 30);
+    model.clear_voxels();
+
+    //only the part inside the model is drawn
+    model.add_line((-10, 5, 5), (40, 5, 5), 1, 1).unwrap();
+    assert_eq!(model.num_of_voxels(), 30);
+    model.add_line((-10, -10, -10), (-1, -1, -1), 1, 1).unwrap();
+    assert_eq!(model.num_of_voxels(), 30);
+    model.clear_voxels();
+
+    //far away ends do not overflow and only the part inside the model is stepped through
+    model
+        .add_line((i32::MIN, 0, 0), (i32::MAX, 0, 0), 1, 1)
+        .unwrap();
+    assert_eq!(model.num_of_voxels(), 30);
+    model
+        .add_line((i32::MIN, i32::MIN, 5), (i32::MAX, i32::MAX, 5), 1, 1)
+        .unwrap();
+    assert_eq!(model.num_of_voxels(), 60);
+    assert!(model.is_voxel_at_pos(29, 29, 5));
+    model.clear_voxels();
+
+    model.add_line((5, 15, 15), (25, 15, 15), 1, 3).unwrap();
+    assert_eq!(model.num_of_voxels(), 21 * 5 + 2);
+    model.clear_voxels();
+
+    //even thicknesses are thicker than the odd one below
+    model.add_line((5, 15, 15), (25, 15, 15), 1, 2).unwrap();
+    assert_eq!(model.num_of_voxels(), 22 * 4);
+    assert!(model.is_voxel_at_pos(26, 16, 16));
+    model.clear_voxels();
+
+    model
+        .add_polyline(&[(0, 0, 0), (10, 0, 0), (10, 10, 0), (0, 0, 0)], 1, 1)
+        .unwrap();
+    assert!(model.is_voxel_at_pos(5, 5, 0));
+    assert!(model.is_voxel_at_pos(10, 5, 0));
+    model.clear_voxels();
+
+    //the overlapping ends of the lines are only placed once
+    model.place_mode = PlaceMode::Error;
+    model
+        .add_polyline(&[(0, 0, 0), (10, 0, 0), (0, 0, 0)], 1, 3)
+        .unwrap();
+    assert!(model.add_line((0, 0, 0), (0, 10, 0), 1, 1).is_err());
+    model.place_mode = PlaceMode::Replace;
+    model.clear_voxels();
+
+    let points = [
+        (0.0, 0.0, 0.0),
+        (0.0, 29.0, 0.0),
+        (29.0, 29.0, 0.0),
+        (29.0, 0.0, 0.0),
+    ];
+    model.add_bezier(points, 1, 0.0).unwrap();
+    assert!(model.is_voxel_at_pos(29, 0, 0));
+    assert!(!model.is_voxel_at_pos(0, 29, 0));
+    model.clear_voxels();
+
+    model.add_catmull_rom(&points, 1, 2.0).unwrap();
+    for point in points.iter() {
+        assert!(model.is_voxel_at_pos(point.0 as u8, point.1 as u8, point.2 as u8));
+    }
+    model.add_catmull_rom(&[], 1, 2.0).unwrap();
+    model.clear_voxels();
+
+    //only the pieces of far away curves that can reach the model are stepped through
+    let points = [
+        (-1e9, 15.0, 15.0),
+        (-1e9, 15.0, 15.0),
+        (1e9, 15.0, 15.0),
+        (1e9, 15.0, 15.0),
+    ];
+    model.add_bezier(points, 1, 0.0).unwrap();
+    assert_eq!(model.num_of_voxels(), 30);
+    model
+        .add_catmull_rom(&[(1e9, 1e9, 1e9), (2e9, 1e9, 0.0)], 1, 1e9)
+        .unwrap();
+    assert_eq!(model.num_of_voxels(), 30);
+    let points = [
+        (-3e38, 0.0, 0.0),
+        (3e38, 0.0, 0.0),
+        (-3e38, 9.0, 9.0),
+        (3e38, 9.0, 9.0),
+    ];
+    model.add_bezier(points, 1, 0.0).unwrap();
+    assert!(model.add_bezier([(f32::NAN, 0.0, 0.0); 4], 1, 1.0).is_err());
+    assert!(model
+        .add_catmull_rom(&[(0.0, 0.0, 0.0), (f32::INFINITY, 0.0, 0.0)], 1, 1.0)
+        .is_err());
+}
+
+#[test]
+fn meshes() {
+    let obj = "mtllib cube.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+v 1 0 1
+v 1 1 1
+v 0 1 1
+usemtl red
+f 1 4 3 2
+f 5 6 7 8
+usemtl blue
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+    let mtl = "newmtl blue\nKd 0 0 1\nnewmtl red\nKd 1 0 0\n";
+    let mesh = Mesh::from_obj(obj, Some(mtl)).unwrap();
+    assert_eq!(mesh.triangles.len(), 12);
+    assert_eq!(mesh.colors[0], Color::new(255, 0, 0, 255));
+    assert_eq!(mesh.colors[1], Color::new(0, 0, 255, 255));
+
+    let mut model = Model::new(10, 10, 10);
+    model.add_mesh(&mesh, 9.0, FillMode::Surface).unwrap();
+    assert_eq!(model.num_of_voxels(), 1000 - 512);
+    assert_eq!(model.get(4, 4, 0), Some(1));
+    assert_eq!(model.get(0, 4, 4), Some(2));
+    assert_eq!(model.get(4, 4, 4), None);
+    model.clear_voxels();
+    model.add_mesh(&mesh, 9.0, FillMode::Solid).unwrap();
+    assert_eq!(model.num_of_voxels(), 1000);
+    assert!(model.add_mesh(&mesh, 10.0, FillMode::Solid).is_err());
+
+    //the same mesh as an ascii and a binary stl
+    let mut ascii = String::from("solid cube\n");
+    let mut binary = vec![0u8; 80];
+    binary.extend_from_slice(&(mesh.triangles.len() as u32).to_le_bytes());
+    for triangle in mesh.triangles.iter() {
+        ascii.push_str("facet normal 0 0 0\nouter loop\n");
+        binary.extend_from_slice(&[0; 12]);
+        for v in triangle.vertices.iter() {
+            ascii.push_str(&format!("vertex {} {} {}\n", v.0, v.1, v.2));
+            for value in [v.0, v.1, v.2].iter() {
+                binary.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        ascii.push_str("endloop\nendfacet\n");
+        binary.extend_from_slice(&[0; 2]);
+    }
+    ascii.push_str("endsolid cube\n");
+    for stl in [ascii.as_bytes(), &binary[..]].iter() {
+        let mesh = Mesh::from_stl(stl).unwrap();
+        assert_eq!(mesh.triangles.len(), 12);
+        let mut model = Model::new(10, 10, 10);
+        model.add_mesh(&mesh, 9.0, FillMode::Solid).unwrap();
+        assert_eq!(model.num_of_voxels(), 1000);
+    }
+
+    match Mesh::from_obj("v 0 0 0\nv 1 x 0\n", None) {
+        Err(VoxError::InvalidMesh { line }) => assert_eq!(line, 2),
+        _ => panic!("expected an invalid mesh error"),
+    }
+
+    //a mesh too large for one model is split into models that line up
+    let mut vox = VoxFile::new(1, 1, 1);
+    let models = vox.add_mesh(&mesh, 259.0, FillMode::Surface).unwrap();
+    assert_eq!(models.len(), 8);
+    assert_eq!(vox.get_palette_color(1), Color::new(255, 0, 0, 255));
+    let first = &vox.models[models[0]];
+    let last = &vox.models[models[7]];
+    assert_eq!(first.size, (256, 256, 256));
+    assert_eq!(last.size, (4, 4, 4));
+    assert_eq!(first.position, Some((128, 128, 128)));
+    assert_eq!(last.position, Some((256 + 2, 256 + 2, 256 + 2)));
+    let total: i32 = models.iter().map(|i| vox.models[*i].num_of_voxels()).sum();
+    assert_eq!(total, 260 * 260 * 260 - 258 * 258 * 258);
+
+    //colors already on the palette are used again and other colors do not replace used ones
+    let models = vox.add_mesh(&mesh, 4.0, FillMode::Surface).unwrap();
+    assert_eq!(vox.models[models[0]].get(2, 2, 0), Some(1));
+    assert_eq!(vox.models[models[0]].get(0, 2, 2), Some(2));
+    assert_eq!(vox.get_palette_color(2), Color::new(0, 0, 255, 255));
+    vox.set_all_palette_color(0, 0, 0, 255);
+    vox.set_palette_color(1, 255, 0, 0, 255);
+    vox.set_palette_color(2, 0, 0, 200, 255);
+    let mut used = Model::new(255, 1, 1);
+    for x in 0..255 {
+        used.add_voxel_at_pos(x, 0, 0, x + 1).unwrap();
+    }
+    vox.add_model(used);
+    let models = vox.add_mesh(&mesh, 4.0, FillMode::Surface).unwrap();
+    assert_eq!(vox.models[models[0]].get(2, 2, 0), Some(1));
+    assert_eq!(vox.models[models[0]].get(0, 2, 2), Some(2));
+    assert_eq!(vox.get_palette_color(2), Color::new(0, 0, 200, 255));
+
+    //sizes that do not fit are errors before any voxels are made
+    let mut model = Model::new(10, 10, 10);
+    assert!(model.add_mesh(&mesh, 1e10, FillMode::Solid).is_err());
+    assert!(vox.add_mesh(&mesh, 1e10, FillMode::Solid).is_err());
+    assert!(vox.add_mesh(&mesh, f32::INFINITY, FillMode::Solid).is_err());
+    let mut far_away = mesh.clone();
+    far_away.triangles[0].vertices[0] = (f32::INFINITY, 0.0, 0.0);
+    assert!(model.add_mesh(&far_away, 1.0, FillMode::Solid).is_err());
+    assert!(vox.add_mesh(&far_away, 1.0, FillMode::Solid).is_err());
+    far_away.triangles[0].vertices[0] = (3e9, 0.0, 0.0);
+    assert!(vox.add_mesh(&far_away, 1.0, FillMode::Solid).is_err());
+
+    //vertices that are not numbers are errors instead of panics
+    match Mesh::from_obj("v 0 0 0\nv 4 0 nan\n", None) {
+        Err(VoxError::InvalidMesh { line }) => assert_eq!(line, 2),
+        _ => panic!("expected an invalid mesh error"),
+    }
+    binary[96..100].copy_from_slice(&f32::NAN.to_le_bytes());
+    match Mesh::from_stl(&binary) {
+        Err(VoxError::InvalidMesh { line }) => assert_eq!(line, 1),
+        _ => panic!("expected an invalid mesh error"),
+    }
+    let mut not_a_number = mesh.clone();
+    not_a_number.triangles[0].vertices[2] = (4.0, 0.0, f32::NAN);
+    assert!(model.add_mesh(&not_a_number, 1.0, FillMode::Solid).is_err());
+    assert!(vox.add_mesh(&not_a_number, 1.0, FillMode::Solid).is_err());
+    assert!(model.add_mesh(&mesh, f32::NAN, FillMode::Solid).is_err());
+
+    //triangles with color index 0 are empty space
+    let mut empty = mesh.clone();
+    for triangle in empty.triangles.iter_mut() {
+        triangle.colorindex = 0;
+    }
+    model.add_mesh(&empty, 9.0, FillMode::Solid).unwrap();
+    assert_eq!(model.num_of_voxels(), 0);
+
+    //each triangle keeps its own color index
+    let mut mesh = Mesh::new();
+    mesh.triangles.push(Triangle::new(
+        [(0.0, 0.0, 0.0), (4.0, 0.0, 0.0), (0.0, 4.0, 0.0)],
+        7,
+    ));
+    let mut model = Model::new(5, 5, 1);
+    model.add_mesh(&mesh, 1.0, FillMode::Surface).unwrap();
+    assert_eq!(model.get(0, 0, 0), Some(7));
+    assert!(model.add_mesh(&Mesh::new(), 1.0, FillMode::Solid).is_ok());
+}
+
+#[test]
+fn fill_with_and_sdf() {
+    let mut model = Model::new(10, 10, 10);
+    model
+        .fill_with(|x, y, z| if x == y && y == z { Some(x) } else { None })
+        .unwrap();
+    //0 is left empty
+    assert_eq!(model.num_of_voxels(), 9);
+    assert_eq!(model.get(9, 9, 9), Some(9));
+    model.place_mode = PlaceMode::Error;
+    assert!(model.fill_with(|_x, _y, _z| Some(1)).is_err());
+    assert_eq!(model.num_of_voxels(), 9);
+    model.place_mode = PlaceMode::KeepExisting;
+    model.fill_with(|_x, _y, _z| Some(1)).unwrap();
+    assert_eq!(model.num_of_voxels(), 1000);
+    assert_eq!(model.get(5, 5, 5), Some(5));
+
+    //the sdf sphere is the same as the sphere shape
+    let mut shape = Model::new(20, 20, 20);
+    shape.add_sphere((10.0, 9.5, 10.0), 6.5, 1).unwrap();
+    let mut model = Model::new(20, 20, 20);
+    model
+        .add_sdf(&sdf::sphere((10.0, 9.5, 10.0), 6.5), 1)
+        .unwrap();
+    assert_eq!(model.num_of_voxels(), shape.num_of_voxels());
+    assert!(shape.voxels().all(|voxel| {
+        let (x, y, z) = voxel.position;
+        model.is_voxel_at_pos(x, y, z)
+    }));
+
+    let a = sdf::cuboid((5.0, 5.0, 5.0), (2.0, 2.0, 2.0));
+    //b covers x from 6 to 9 and goes past a on the other axes
+    let b = sdf::cuboid((7.5, 5.0, 5.0), (2.0, 4.0, 4.0));
+    let count = |shape: sdf::Sdf| {
+        let mut model = Model::new(16, 16, 16);
+        model.add_sdf(&shape, 1).unwrap();
+        model.num_of_voxels()
+    };
+    assert_eq!(count(a.clone()), 125);
+    assert_eq!(count(a.clone().union(b.clone())), 125 + 324 - 50);
+    assert_eq!(count(a.clone().intersect(b.clone())), 50);
+    assert_eq!(count(a.clone().subtract(b.clone())), 75);
+    assert_eq!(count(a.clone().translate((100.0, 0.0, 0.0))), 0);
+    //copies at x = 0, 8 and 16 which are cut off by the model
+    let posts = sdf::cuboid((0.0, 5.0, 5.0), (2.0, 2.0, 2.0)).repeat((8.0, 0.0, 0.0));
+    assert_eq!(count(posts), (3 + 5 + 2) * 25);
+    assert_eq!(
+        count(sdf::capsule((2.0, 2.0, 2.0), (12.0, 2.0, 2.0), 0.0)),
+        11
+    );
+
+    let mut vox = VoxFile::new(1, 1, 1);
+    let ball = sdf::sphere((0.0, 0.0, 0.0), 3.0);
+    let models = vox.add_sdf(&ball, (-300, -3, -3), (3, 3, 3), 2).unwrap();
+    //only the part of the area with the ball gets a model
+    assert_eq!(models.len(), 1);
+    let model = &vox.models[models[0]];
+    assert_eq!(model.size, (48, 7, 7));
+    assert_eq!(model.position, Some((-44 + 24, 0, 0)));
+    assert_eq!(model.get(44, 3, 3), Some(2));
+    assert!(vox.add_sdf(&ball, (0, 0, 0), (-1, -1, -1), 1).is_err());
+    //bounds too far apart are an error instead of overflowing or filling for hours
+    let (min, max) = (i32::MIN, i32::MAX);
+    assert!(vox
+        .add_sdf(&ball, (min, min, min), (max, max, max), 1)
+        .is_err());
+    assert!(vox
+        .add_sdf(&ball, (0, 0, 0), (4095, 4095, 4095), 1)
+        .is_err());
+    //an area reaching the largest position still fits
+    let models = vox.add_sdf(&ball, (max - 3, 0, 0), (max, 0, 0), 1).unwrap();
+    assert!(models.is_empty());
+    assert_eq!(vox.models.len(), 2);
+}
+
+#[test]
+fn csg() {
+    let mut a = Model::new(4, 4, 4);
+    a.add_cube(0, 0, 0, 4, 4, 4, 1).unwrap();
+    let mut b = Model::new(4, 4, 4);
+    b.add_cube(0, 0, 0, 4, 4, 4, 2).unwrap();
+
+    //the other model wins with the default place mode
+    let mut model = a.clone();
+    model.union(&b, (2, 0, 0)).unwrap();
+    assert_eq!(model.size, (6, 4, 4));
+    assert_eq!(model.num_of_voxels(), 6 * 16);
+    assert_eq!(model.get(2, 0, 0), Some(2));
+    assert_eq!(model.get(0, 0, 0), Some(1));
+
+    //nothing changes when a voxel overlaps with PlaceMode::Error
+    let mut model = a.clone();
+    model.place_mode = PlaceMode::Error;
+    assert!(model.union(&b, (-2, 0, 0)).is_err());
+    assert_eq!(model.size, (4, 4, 4));
+    model.union(&b, (0, 0, 4)).unwrap();
+    assert_eq!(model.num_of_voxels(), 128);
+
+    //a negative offset moves the voxels and keeps them at the same place in the scene
+    let mut model = a.clone();
+    model.position = Some((10, 10, 10));
+    model.union(&b, (-3, 0, 0)).unwrap();
+    assert_eq!(model.size, (7, 4, 4));
+    assert_eq!(model.get(4, 0, 0), Some(1));
+    assert_eq!(model.get(3, 0, 0), Some(2));
+    //voxel 0 was at 10 - 4 / 2 and is now voxel 3 of a model with a size of 7
+    assert_eq!(model.position, Some((10 - 2 - 3 + 7 / 2, 10, 10)));
+
+    let mut large = Model::new(200, 1, 1);
+    large.add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    let mut model = large.clone();
+    assert!(model.union(&large, (100, 0, 0)).is_err());
+    assert!(model.xor(&large, (-100, 0, 0)).is_err());
+    assert_eq!(model.size, (200, 1, 1));
+    model.union(&large, (56, 0, 0)).unwrap();
+    assert_eq!(model.size, (256, 1, 1));
+
+    //offsets far away are errors instead of overflowing
+    assert!(model.union(&large, (i32::MIN, 0, 0)).is_err());
+    assert!(model.xor(&large, (0, i32::MAX, 0)).is_err());
+    model.place_mode = PlaceMode::Error;
+    assert!(model.union(&large, (i32::MAX, 0, 0)).is_err());
+    model.subtract(&large, (i32::MAX, 0, 0));
+    model.intersect(&large, (i32::MAX, i32::MIN, 0));
+    assert_eq!(model.num_of_voxels(), 0);
+
+    //only the part of the other model inside this one is used
+    let mut model = a.clone();
+    model.subtract(&b, (2, 2, -2));
+    assert_eq!(model.num_of_voxels(), 64 - 8);
+    model.subtract(&b, (100, 0, 0));
+    assert_eq!(model.num_of_voxels(), 64 - 8);
+
+    let mut model = a.clone();
+    model.intersect(&b, (3, 3, 3));
+    assert_eq!(model.num_of_voxels(), 1);
+    assert_eq!(model.get(3, 3, 3), Some(1));
+    model.intersect(&b, (-10, 0, 0));
+    assert_eq!(model.num_of_voxels(), 0);
+
+    let mut model = a.clone();
+    model.xor(&b, (0, 0, 2)).unwrap();
+    assert_eq!(model.size, (4, 4, 6));
+    assert_eq!(model.num_of_voxels(), 64);
+    assert_eq!(model.get(0, 0, 1), Some(1));
+    assert_eq!(model.get(0, 0, 2), None);
+    assert_eq!(model.get(0, 0, 5), Some(2));
+}
+
+#[test]
+fn rotating_and_moving_voxels() {
+    let mut model = Model::new(5, 6, 7);
+    model.set_storage(Storage::Dense);
+    model.add_cube(0, 0, 0, 2, 3, 4, 1).unwrap();
+    model.add_voxel_at_pos(4, 5, 6, 2).unwrap();
+    let original = model.clone();
+    let same = |a: &Model, b: &Model| {
+        a.size == b.size
+            && a.num_of_voxels() == b.num_of_voxels()
+            && a.voxels().all(|voxel| {
+                let (x, y, z) = voxel.position;
+                b.get(x, y, z) == Some(voxel.colorindex)
+            })
+    };
+
+    for axis in [Axis::X, Axis::Y, Axis::Z].iter() {
+        model.rotate_90(*axis, 1);
+        assert_eq!(model.num_of_voxels(), original.num_of_voxels());
+        model.rotate_90(*axis, 3);
+        assert!(same(&model, &original));
+        model.rotate_90(*axis, -2);
+        model.rotate_90(*axis, 2);
+        assert!(same(&model, &original));
+        model.mirror(*axis);
+        assert!(!same(&model, &original));
+        model.mirror(*axis);
+        assert!(same(&model, &original));
+    }
+    assert_eq!(model.storage(), Storage::Dense);
+
+    model.rotate_90(Axis::Y, 1);
+    assert_eq!(model.size, (7, 6, 5));
+    //z goes to x and x goes to -z
+    assert_eq!(model.get(6, 5, 0), Some(2));
+    model.rotate_90(Axis::Y, -1);
+
+    //every rotation keeps the voxels inside the model
+    for byte in 0..128 {
+        if let Some(rotation) = Rotation::from_byte(byte) {
+            let mut rotated = original.clone();
+            rotated.apply_rotation(rotation);
+            assert_eq!(rotated.num_of_voxels(), original.num_of_voxels());
+            rotated.apply_rotation(rotation.inverse());
+            assert!(same(&rotated, &original));
+        }
+    }
+
+    model.translate(1, 0, 0, Overflow::Clip).unwrap();
+    assert_eq!(model.num_of_voxels(), original.num_of_voxels() - 1);
+    assert_eq!(model.get(1, 0, 0), Some(1));
+    model.translate(-1, 0, 0, Overflow::Clip).unwrap();
+    assert_eq!(model.get(0, 0, 0), Some(1));
+
+    let mut model = original.clone();
+    model.translate(-1, -1, 0, Overflow::Wrap).unwrap();
+    assert_eq!(model.get(4, 5, 0), Some(1));
+    assert_eq!(model.get(3, 4, 6), Some(2));
+    model.translate(1, 1, 7, Overflow::Wrap).unwrap();
+    assert!(same(&model, &original));
+
+    assert!(model.translate(0, 0, 250, Overflow::Grow).is_err());
+    assert!(model.translate(i32::MIN, 0, 0, Overflow::Grow).is_err());
+    assert!(same(&model, &original));
+    let mut clipped = original.clone();
+    clipped.translate(0, i32::MAX, 0, Overflow::Clip).unwrap();
+    assert_eq!(clipped.num_of_voxels(), 0);
+
+    //growing keeps the model in the same place in the scene so only the voxels move
+    let scene = |model: &Model, local: (i32, i32, i32)| {
+        let (position, size) = (model.position.unwrap(), model.size);
+        (
+            position.0 - size.0 as i32 / 2 + local.0,
+            position.1 - size.1 as i32 / 2 + local.1,
+            position.2 - size.2 as i32 / 2 + local.2,
+        )
+    };
+    model.position = Some((10, 10, 10));
+    let before = scene(&model, (4, 5, 6));
+    model.translate(0, 0, 249, Overflow::Grow).unwrap();
+    assert_eq!(model.size, (5, 6, 256));
+    assert_eq!(model.get(4, 5, 255), Some(2));
+    assert_eq!(
+        scene(&model, (4, 5, 255)),
+        (before.0, before.1, before.2 + 249)
+    );
+
+    //voxels moved below 0 move the others up
+    let mut model = original.clone();
+    model.position = Some((10, 10, 10));
+    let before = scene(&model, (4, 5, 6));
+    model.translate(-3, 0, 0, Overflow::Grow).unwrap();
+    assert_eq!(model.size, (8, 6, 7));
+    assert_eq!(model.get(0, 0, 0), Some(1));
+    assert_eq!(model.get(4, 5, 6), Some(2));
+    assert_eq!(scene(&model, (4, 5, 6)), (before.0 - 3, before.1, before.2));
+}
+
+#[test]
+fn resampling() {
+    let mut original = Model::new(5, 4, 3);
+    original.set_storage(Storage::Dense);
+    original.add_cube(0, 0, 0, 2, 2, 2, 1).unwrap();
+    original.add_voxel_at_pos(4, 3, 2, 2).unwrap();
+    original.position = Some((5, 5, 5));
+
+    //scaling up and then down gives back the same voxels
+    let mut model = original.clone();
+    model.scale(2.0).unwrap();
+    assert_eq!(model.size, (10, 8, 6));
+    assert_eq!(model.num_of_voxels(), original.num_of_voxels() * 8);
+    assert_eq!(model.get(9, 7, 5), Some(2));
+    assert_eq!(model.storage(), Storage::Dense);
+    assert_eq!(model.position, Some((5, 5, 5)));
+    model.downsample(2).unwrap();
+    assert_eq!(model.size, original.size);
+    assert!(original.voxels().all(|voxel| {
+        let (x, y, z) = voxel.position;
+        model.get(x, y, z) == Some(voxel.colorindex)
+    }));
+    assert_eq!(model.num_of_voxels(), original.num_of_voxels());
+
+    let mut model = original.clone();
+    model.scale(1.5).unwrap();
+    assert_eq!(model.size, (8, 6, 5));
+    assert_eq!(model.get(7, 5, 4), Some(2));
+    assert!(model.scale(0.0).is_err());
+    assert!(model.scale(f32::NAN).is_err());
+    assert!(model.scale(40.0).is_err());
+    assert_eq!(model.size, (8, 6, 5));
+
+    //blocks at the edge are smaller and a single voxel is half of a 1 by 1 by 2 block
+    let mut model = original.clone();
+    model.downsample(2).unwrap();
+    assert_eq!(model.size, (3, 2, 2));
+    assert_eq!(model.get(0, 0, 0), Some(1));
+    assert_eq!(model.get(2, 1, 1), Some(2));
+    assert_eq!(model.num_of_voxels(), 2);
+    assert!(model.downsample(0).is_err());
+
+    //a block with less than half of it filled is empty
+    let mut model = Model::new(2, 2, 2);
+    model.add_cube(0, 0, 0, 2, 1, 1, 1).unwrap();
+    model.add_voxel_at_pos(0, 1, 0, 3).unwrap();
+    model.downsample(2).unwrap();
+    assert_eq!(model.num_of_voxels(), 0);
+
+    let mut vox = VoxFile::new(2, 2, 2);
+    vox.set_palette_color(1, 200, 200, 200, 255);
+    vox.set_palette_color(2, 0, 0, 0, 255);
+    vox.set_palette_color(3, 100, 100, 100, 255);
+    vox.models[0].add_cube(0, 0, 0, 2, 2, 1, 1).unwrap();
+    vox.models[0].add_cube(0, 0, 1, 2, 1, 2, 2).unwrap();
+    let mut majority = vox.models[0].clone();
+    majority.downsample(2).unwrap();
+    assert_eq!(majority.get(0, 0, 0), Some(1));
+    let palette = vox.palette;
+    vox.models[0].downsample_average(2, &palette).unwrap();
+    assert_eq!(vox.models[0].get(0, 0, 0), Some(3));
+    //voxels with color index 0 are left out of the blocks
+    let mut model = Model::new(2, 1, 1);
+    let empty = Voxel {
+        position: (0, 0, 0),
+        colorindex: 0,
+    };
+    model.add_voxel(empty).unwrap();
+    model.add_voxel_at_pos(1, 0, 0, 2).unwrap();
+    model.downsample_average(2, &palette).unwrap();
+    assert_eq!(model.get(0, 0, 0), Some(2));
+
+    //a scaled model larger than 256 is split around its position
+    let mut model = original.clone();
+    model.layer = Some(0);
+    let models = vox.add_scaled_model(&model, 60.0).unwrap();
+    assert_eq!(models.len(), 2);
+    let total: i32 = models.iter().map(|i| vox.models[*i].num_of_voxels()).sum();
+    assert_eq!(total, 120 * 120 * 120 + 60 * 60 * 60);
+    assert!(models.iter().all(|i| vox.models[*i].layer == Some(0)));
+    let first = &vox.models[models[0]];
+    assert_eq!(first.size, (256, 240, 180));
+    assert_eq!(first.position, Some((5 - 150 + 128, 5, 5)));
+    let count = vox.models.len();
+    for factor in [-1.0, 0.0, f32::NAN, f32::INFINITY, 1e6, 1e12].iter() {
+        assert!(vox.add_scaled_model(&model, *factor).is_err());
+    }
+    assert_eq!(vox.models.len(), count);
+
+    //the parts are turned around the position by the rotation of the model
+    model.rotation = Some(Rotation::rotate_z90());
+    model.name = Some(String::from("statue"));
+    model.hidden = Some(true);
+    let models = vox.add_scaled_model(&model, 60.0).unwrap();
+    let offset = Rotation::rotate_z90().apply((-150 + 128, 0, 0));
+    let first = &vox.models[models[0]];
+    assert_eq!(
+        first.position,
+        Some((5 + offset.0, 5 + offset.1, 5 + offset.2))
+    );
+    assert!(models.iter().all(|i| {
+        let part = &vox.models[*i];
+        part.rotation == model.rotation
+            && part.name == model.name
+            && part.hidden == Some(true)
+            && part.layer == Some(0)
+    }));
+}